@@ -0,0 +1,101 @@
+use std::num::ParseIntError;
+use subnetcalc::errors::NetworkError;
+use subnetcalc::subnet::Network;
+
+/// Everything a command can fail with, so `main` can tell the causes apart.
+pub enum CliError {
+    Network(NetworkError),
+    /// One entry of a network list failed to parse, kept next to the offending input
+    Entry(String, NetworkError),
+    /// A host demand the parent network had no room left for
+    Unfit(u32, Network, NetworkError),
+    Io(std::io::Error),
+    /// Input or output in the wrong shape, e.g. a count that is not a number
+    Format(String),
+}
+
+impl std::error::Error for CliError {}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Network(e) => write!(f, "{}", e),
+            CliError::Entry(input, e) => write!(f, "`{}`: {}", input, e),
+            CliError::Unfit(hosts, parent, e) => {
+                write!(f, "cannot fit {} hosts in {}: {}", hosts, parent, e)
+            }
+            CliError::Io(e) => write!(f, "I/O error: {}", e),
+            CliError::Format(message) => write!(f, "Invalid format: {}", message),
+        }
+    }
+}
+
+/// Same as `Display`, `main` reports a returned error through `Debug`.
+impl std::fmt::Debug for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl From<NetworkError> for CliError {
+    fn from(error: NetworkError) -> Self {
+        CliError::Network(error)
+    }
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(error: std::io::Error) -> Self {
+        CliError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for CliError {
+    fn from(error: serde_json::Error) -> Self {
+        CliError::Format(error.to_string())
+    }
+}
+
+impl From<ParseIntError> for CliError {
+    fn from(error: ParseIntError) -> Self {
+        CliError::Format(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_error_variants() {
+        let error = CliError::from(NetworkError::InvalidPrefixLength);
+        assert!(matches!(error, CliError::Network(_)));
+        assert_eq!(error.to_string(), "Prefix length must be between 0 and 32.");
+
+        let error = CliError::Entry("10.0.0.0/33".into(), NetworkError::InvalidPrefixLength);
+        assert_eq!(
+            error.to_string(),
+            "`10.0.0.0/33`: Prefix length must be between 0 and 32."
+        );
+
+        let parent = "192.168.1.0/24".parse().unwrap();
+        let error = CliError::Unfit(300, parent, NetworkError::NoFreeBlock);
+        assert!(error
+            .to_string()
+            .starts_with("cannot fit 300 hosts in 192.168.1.0/24: "));
+
+        let error = CliError::from(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "demands.txt",
+        ));
+        assert!(matches!(error, CliError::Io(_)));
+        assert_eq!(error.to_string(), "I/O error: demands.txt");
+
+        let error = CliError::from("12x".parse::<u32>().unwrap_err());
+        assert!(matches!(error, CliError::Format(_)));
+        assert_eq!(
+            error.to_string(),
+            "Invalid format: invalid digit found in string"
+        );
+        assert_eq!(format!("{:?}", error), error.to_string());
+    }
+}
@@ -0,0 +1,1759 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use colored::*;
+use error::CliError;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::io::{IsTerminal, Write};
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+use subnetcalc::errors::NetworkError;
+use subnetcalc::plan::SubnetPlan;
+use subnetcalc::subnet::{HostCountPolicy, IpNetwork, Ipv6Network, Network, NetworkInfo};
+use subnetcalc::utils;
+use subnetcalc::vlsm::VlsmAllocator;
+use theme::Theme;
+
+mod error;
+mod theme;
+
+#[derive(Parser)]
+#[command(name = "subnetcalc", about = "A tool for subnet calculations")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Commands,
+    /// Output format for info, aggregate, summarize and mask, explicit flags take precedence
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "SUBNETCALC_FORMAT",
+        default_value_t = OutputFormat::Text
+    )]
+    pub format: OutputFormat,
+    /// Colour palette for info, aggregate and mask output
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "SUBNETCALC_THEME",
+        default_value_t = Theme::Dark
+    )]
+    pub theme: Theme,
+    /// Maximum line width for tables, defaults to the terminal width
+    #[arg(long, global = true)]
+    pub width: Option<usize>,
+    /// Prefix length for networks given without one, instead of the classful default
+    #[arg(
+        long,
+        global = true,
+        env = "SUBNETCALC_DEFAULT_PREFIX",
+        value_parser = clap::value_parser!(u32).range(0..=32)
+    )]
+    pub default_prefix: Option<u32>,
+    /// Write the command's output to this file as plain text instead of the terminal, the file
+    /// is only replaced once the command succeeds
+    #[arg(long, global = true)]
+    pub output: Option<PathBuf>,
+    /// Separate the values of hosts, summarize and filter with this character, e.g. `,`, `\t`
+    /// or `\0`, instead of one per line
+    #[arg(long, global = true, value_parser = parse_delimiter)]
+    pub delimiter: Option<char>,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+    Table,
+}
+
+impl OutputFormat {
+    /// A subcommand's `--json` flag wins over `--format` and `SUBNETCALC_FORMAT`.
+    fn or_json(self, json: bool) -> Self {
+        if json {
+            OutputFormat::Json
+        } else {
+            self
+        }
+    }
+}
+
+/// Which part of the network information `info` prints.
+#[derive(Clone, Copy, PartialEq)]
+enum InfoView {
+    Full,
+    /// Only the values, one per line
+    Quiet,
+    PrefixOnly,
+    Oneline,
+}
+
+/// Operations `examples` can demonstrate.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Example {
+    Info,
+    Aggregate,
+    Summarize,
+    Split,
+    Mask,
+}
+
+impl Example {
+    /// The canned arguments each example runs with, the counts are parsed back out of them.
+    fn args(self) -> &'static [&'static str] {
+        match self {
+            Example::Info => &["192.168.1.0/26"],
+            Example::Aggregate => &["192.168.100.0/27", "192.168.100.32/27", "192.168.100.64/26"],
+            Example::Summarize => &["10.0.0.0/24", "10.0.1.0/24", "10.0.4.0/24"],
+            Example::Split => &["192.168.1.0/24", "--into", "4"],
+            Example::Mask => &["172.16.0.0", "90", "350"],
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Aggregate multiple networks into one larger network
+    Aggregate {
+        /// List of networks to aggregate (in CIDR notation)
+        #[arg(required = true)]
+        networks: Vec<String>,
+        /// List the input networks under the result, flagging any it does not contain
+        #[arg(long)]
+        show_members: bool,
+        /// Print the result as a JSON array
+        #[arg(long, conflicts_with = "show_members")]
+        json: bool,
+        /// Refuse aggregates shorter than this prefix length
+        #[arg(long)]
+        min_prefix: Option<u32>,
+        /// Leave out the trailing note on how many entries were combined
+        #[arg(long)]
+        quiet: bool,
+        /// Only merge adjacent or contained networks, printing every block needed to cover
+        /// exactly the input addresses
+        #[arg(long, conflicts_with_all = ["show_members", "min_prefix"])]
+        exact: bool,
+        /// Report the addresses the result absorbed beyond the inputs, failing if there are any
+        #[arg(long, conflicts_with_all = ["json", "exact"])]
+        verify: bool,
+    },
+    /// Summarize networks into the minimal list covering exactly the same addresses
+    Summarize {
+        /// List of networks to summarize (in CIDR notation)
+        #[arg(required = true)]
+        networks: Vec<String>,
+        /// Print the result as a JSON array
+        #[arg(long)]
+        json: bool,
+        /// Leave out the trailing note on how many entries were combined
+        #[arg(long)]
+        quiet: bool,
+        /// Print only the number of resulting blocks and the addresses they cover
+        #[arg(long, conflicts_with = "json")]
+        summary_only: bool,
+    },
+    /// List the usable host addresses of a network
+    Hosts {
+        /// Network to list hosts for (in CIDR notation)
+        #[arg(required = true)]
+        network: String,
+        /// Print only the number of usable hosts
+        #[arg(long)]
+        count_only: bool,
+    },
+    /// List every address of a network, including the network and broadcast addresses
+    Expand {
+        /// Network to expand (in CIDR notation)
+        #[arg(required = true)]
+        network: String,
+        /// Expand networks larger than a /16 anyway
+        #[arg(long)]
+        force: bool,
+    },
+    /// Split a network into equal subnets
+    Split {
+        /// Network to split (in CIDR notation)
+        #[arg(required = true)]
+        network: String,
+        /// Minimum number of subnets, rounded up to a power of two
+        #[arg(long)]
+        into: u32,
+        /// Draw the division as a text bar
+        #[arg(long)]
+        ascii: bool,
+        /// Note subnets whose mask boundary falls inside an octet
+        #[arg(long, conflicts_with = "ascii")]
+        warn_unaligned: bool,
+    },
+    /// Display information about a specific network
+    Info {
+        /// Network to display information for (in CIDR notation)
+        #[arg(required = true)]
+        network: String,
+        /// Print the information as a JSON object
+        #[arg(long)]
+        json: bool,
+        /// Print only the values, one per line, without labels
+        #[arg(long, conflicts_with = "json")]
+        quiet: bool,
+        /// Print only the prefix length, e.g. to convert a dotted netmask
+        #[arg(long, conflicts_with_all = ["json", "quiet"])]
+        prefix_only: bool,
+        /// Print a single summary line
+        #[arg(long, conflicts_with_all = ["json", "quiet", "prefix_only"])]
+        oneline: bool,
+        /// Override how usable hosts are counted
+        #[arg(long, value_enum)]
+        policy: Option<HostCountPolicy>,
+    },
+    /// Check whether each input is a valid network and explain what is wrong otherwise
+    Validate {
+        /// Tokens to validate (in CIDR notation)
+        #[arg(required = true)]
+        tokens: Vec<String>,
+    },
+    /// Print the addresses that fall inside a network, one per line
+    Filter {
+        /// Network to match against (in CIDR notation)
+        #[arg(required = true)]
+        network: String,
+        /// Addresses to check, read from stdin when none are given
+        ips: Vec<String>,
+        /// Print the addresses outside the network instead
+        #[arg(long)]
+        invert: bool,
+    },
+    /// Compare two networks and show how they relate to each other
+    Compare {
+        /// First network (in CIDR notation)
+        #[arg(required = true)]
+        network_a: String,
+        /// Second network (in CIDR notation)
+        #[arg(required = true)]
+        network_b: String,
+    },
+    /// Show which networks were added and removed between two lists
+    Diff {
+        /// File with the old networks, one per line
+        #[arg(long)]
+        old: PathBuf,
+        /// File with the new networks, one per line
+        #[arg(long)]
+        new: PathBuf,
+    },
+    /// Allocate subnets for a list of host demands out of a parent network
+    Plan {
+        /// Parent network to allocate from (in CIDR notation)
+        #[arg(required = true)]
+        parent: String,
+        /// File with one host count per line
+        #[arg(long)]
+        demands_file: PathBuf,
+    },
+    /// Allocate subnets for host demands and render the result, e.g. as an HTML page
+    Report {
+        /// Parent network to allocate from (in CIDR notation)
+        #[arg(required = true)]
+        parent: String,
+        /// Comma separated host counts
+        #[arg(long, required = true, value_delimiter = ',')]
+        demands: Vec<u32>,
+        /// Write a self-contained HTML page instead of the text division
+        #[arg(long)]
+        html: bool,
+    },
+    /// Find the first free subnet of a given size within a parent network
+    Allocate {
+        /// Parent network to allocate from (in CIDR notation)
+        #[arg(required = true)]
+        parent: String,
+        /// Prefix length of the subnet to allocate
+        #[arg(long)]
+        prefix: u32,
+        /// File with the networks already in use, one per line
+        #[arg(long)]
+        used: PathBuf,
+    },
+    /// Print the smallest prefix whose subnets hold a given number of hosts
+    Fit {
+        /// Number of usable hosts each subnet needs
+        #[arg(long)]
+        hosts: u32,
+    },
+    /// Show worked examples, computed live on canned input
+    Examples {
+        /// Only show the example for this command
+        #[arg(value_enum)]
+        command: Option<Example>,
+    },
+    /// Print a reference table of every prefix length with its masks and sizes
+    #[command(alias = "table")]
+    Reference,
+    /// Calculate the mask for a given number of subnets and hosts per subnet
+    Mask {
+        /// Network to calculate the mask for
+        #[arg(required = true)]
+        network: String,
+        /// Number of required subnets
+        #[arg(required = true)]
+        subnets: u32,
+        /// Number of required hosts per subnet
+        #[arg(required = true)]
+        hosts: u32,
+        /// Print the plan as a JSON object
+        #[arg(long)]
+        json: bool,
+        /// Print only the dotted netmask
+        #[arg(long, conflicts_with = "json")]
+        quiet: bool,
+    },
+}
+
+fn main() -> Result<(), CliError> {
+    env_logger::Builder::from_default_env()
+        .filter(None, log::LevelFilter::Info)
+        .init();
+
+    let cli = Cli::parse();
+    cli.theme.install();
+
+    // `--output` goes to a staging file first, so a failing command leaves the target untouched
+    let staged = cli.output.as_deref().map(staging_path);
+    let mut writer: Box<dyn Write> = match &staged {
+        Some(path) => {
+            colored::control::set_override(false);
+            Box::new(std::io::BufWriter::new(std::fs::File::create(path)?))
+        }
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let out = writer.as_mut();
+    let width = cli.width.or_else(terminal_width);
+
+    let result = match &cli.command {
+        Commands::Aggregate {
+            networks,
+            json,
+            quiet,
+            exact: true,
+            ..
+        } => handle_aggregate_exact(
+            out,
+            networks,
+            cli.format.or_json(*json),
+            width,
+            *quiet,
+            cli.default_prefix,
+        ),
+        Commands::Aggregate {
+            networks,
+            show_members,
+            json,
+            min_prefix,
+            quiet,
+            exact: false,
+            verify,
+        } => handle_aggregate(
+            out,
+            networks,
+            cli.format.or_json(*json),
+            width,
+            AggregateOptions {
+                show_members: *show_members,
+                min_prefix: *min_prefix,
+                quiet: *quiet,
+                verify: *verify,
+            },
+            cli.default_prefix,
+        ),
+        Commands::Summarize {
+            networks,
+            json,
+            quiet,
+            summary_only,
+        } => handle_summarize(
+            out,
+            networks,
+            cli.format.or_json(*json),
+            width,
+            SummarizeOptions {
+                quiet: *quiet,
+                summary_only: *summary_only,
+            },
+            cli.delimiter,
+            cli.default_prefix,
+        ),
+        Commands::Hosts {
+            network,
+            count_only,
+        } => handle_hosts(out, network, *count_only, cli.delimiter, cli.default_prefix),
+        Commands::Expand { network, force } => {
+            handle_expand(out, network, *force, cli.default_prefix)
+        }
+        Commands::Split {
+            network,
+            into,
+            ascii,
+            warn_unaligned,
+        } => handle_split(
+            out,
+            network,
+            *into,
+            *ascii,
+            *warn_unaligned,
+            cli.default_prefix,
+        ),
+        Commands::Info {
+            network,
+            json,
+            quiet,
+            prefix_only,
+            oneline,
+            policy,
+        } => {
+            let view = match (*quiet, *prefix_only, *oneline) {
+                (true, _, _) => InfoView::Quiet,
+                (_, true, _) => InfoView::PrefixOnly,
+                (_, _, true) => InfoView::Oneline,
+                _ => InfoView::Full,
+            };
+            handle_info(
+                out,
+                network,
+                cli.format.or_json(*json),
+                width,
+                view,
+                *policy,
+                cli.default_prefix,
+            )
+        }
+        Commands::Validate { tokens } => handle_validate(out, tokens, cli.default_prefix),
+        Commands::Filter {
+            network,
+            ips,
+            invert,
+        } => handle_filter(
+            out,
+            network,
+            ips,
+            *invert,
+            cli.delimiter,
+            cli.default_prefix,
+        ),
+        Commands::Compare {
+            network_a,
+            network_b,
+        } => handle_compare(out, network_a, network_b, cli.default_prefix),
+        Commands::Diff { old, new } => handle_diff(out, old, new, cli.default_prefix),
+        Commands::Plan {
+            parent,
+            demands_file,
+        } => handle_plan(out, parent, demands_file, cli.default_prefix),
+        Commands::Report {
+            parent,
+            demands,
+            html,
+        } => handle_report(out, parent, demands, *html, cli.default_prefix),
+        Commands::Allocate {
+            parent,
+            prefix,
+            used,
+        } => handle_allocate(out, parent, *prefix, used, cli.default_prefix),
+        Commands::Fit { hosts } => handle_fit(out, *hosts),
+        Commands::Examples { command } => handle_examples(out, *command, width),
+        Commands::Reference => handle_reference(out, width),
+        Commands::Mask {
+            network,
+            subnets,
+            hosts,
+            json,
+            quiet,
+        } => handle_mask(
+            out,
+            network,
+            *subnets,
+            *hosts,
+            cli.format.or_json(*json),
+            *quiet,
+            cli.default_prefix,
+        ),
+    };
+    let result = match result.and_then(|()| Ok(out.flush()?)) {
+        // The reader went away early, e.g. `hosts 10.0.0.0/8 | head`, which is not a failure
+        Err(CliError::Io(e)) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    };
+    drop(writer);
+    if let (Some(staged), Some(target)) = (staged, &cli.output) {
+        match result {
+            Ok(()) => std::fs::rename(&staged, target)?,
+            Err(_) => {
+                let _ = std::fs::remove_file(&staged);
+            }
+        }
+    }
+    result
+}
+
+/// A hidden sibling of `path`, renaming within the same directory replaces the target at once.
+fn staging_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.partial", name))
+}
+
+/// The `aggregate` flags besides the output format.
+#[derive(Clone, Copy, Default)]
+struct AggregateOptions {
+    show_members: bool,
+    min_prefix: Option<u32>,
+    quiet: bool,
+    verify: bool,
+}
+
+fn handle_aggregate(
+    out: &mut dyn Write,
+    networks: &[String],
+    format: OutputFormat,
+    width: Option<usize>,
+    options: AggregateOptions,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    // Checked here rather than by clap so `SUBNETCALC_FORMAT` is covered too
+    if format != OutputFormat::Text {
+        if options.show_members {
+            return Err(CliError::Format(
+                "--show-members only reports as text".to_string(),
+            ));
+        }
+        if options.verify {
+            return Err(CliError::Format(
+                "--verify only reports as text".to_string(),
+            ));
+        }
+    }
+    if networks.iter().any(|arg| arg.contains(':')) {
+        return handle_dual_stack_aggregate(out, networks, format, options, default_prefix);
+    }
+    let parsed_networks: Vec<Network> = parse_networks(networks, default_prefix)?;
+    // Errors are returned rather than printed, so main reports them on stderr and exits nonzero
+    let aggregated_network = match options.min_prefix {
+        Some(min_prefix) => Network::aggregate_networks_with_floor(&parsed_networks, min_prefix),
+        None => Network::aggregate_networks(&parsed_networks),
+    }?;
+    if format != OutputFormat::Text {
+        return print_records(out, &[aggregated_network.describe()], format, width);
+    }
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Aggregated Network"),
+        theme::network(&aggregated_network.to_string())
+    )?;
+    if options.show_members {
+        display_members(out, &parsed_networks, |member| {
+            aggregated_network.contains_network(member)
+        })?;
+    }
+    if !options.quiet {
+        warn_classful_span(&aggregated_network);
+        write_aggregate_note(out, parsed_networks.len(), aggregated_network.prefix)?;
+    }
+    if options.verify {
+        let inputs: Vec<IpNetwork> = parsed_networks.into_iter().map(IpNetwork::V4).collect();
+        write_verification(out, &[IpNetwork::V4(aggregated_network)], &inputs)?;
+    }
+    Ok(())
+}
+
+/// Compares the aggregates' coverage with the inputs, failing with `Lossy` when they absorbed
+/// addresses beyond the inputs and with `NotContained` when an input is left out.
+fn write_verification(
+    out: &mut dyn Write,
+    aggregated: &[IpNetwork],
+    inputs: &[IpNetwork],
+) -> Result<(), CliError> {
+    let mut absorbed = Vec::new();
+    for aggregate in aggregated {
+        absorbed.extend(IpNetwork::absorbed_blocks(aggregate, inputs)?);
+    }
+    let uncovered: Vec<&IpNetwork> = inputs
+        .iter()
+        .filter(|input| !aggregated.iter().any(|net| net.contains_network(input)))
+        .collect();
+    if absorbed.is_empty() && uncovered.is_empty() {
+        writeln!(
+            out,
+            "verified: the aggregate covers exactly the input addresses"
+        )?;
+        return Ok(());
+    }
+    if !absorbed.is_empty() {
+        let extra = absorbed
+            .iter()
+            .map(IpNetwork::address_count)
+            .fold(0u128, u128::saturating_add);
+        writeln!(
+            out,
+            "{}: absorbed {} extra {} not in the inputs:",
+            theme::error("verify failed").bold(),
+            display_wide_count(extra),
+            if extra == 1 { "address" } else { "addresses" }
+        )?;
+        for block in &absorbed {
+            writeln!(
+                out,
+                "  {} ({} - {})",
+                theme::network(&block.to_string()),
+                block.network_address(),
+                block.last_address()
+            )?;
+        }
+    }
+    if !uncovered.is_empty() {
+        writeln!(
+            out,
+            "{}: {} not covered by the aggregate:",
+            theme::error("verify failed").bold(),
+            count_networks(uncovered.len())
+        )?;
+        for input in &uncovered {
+            writeln!(out, "  {}", theme::network(&input.to_string()))?;
+        }
+    }
+    if absorbed.is_empty() {
+        Err(NetworkError::NotContained.into())
+    } else {
+        Err(NetworkError::Lossy.into())
+    }
+}
+
+fn handle_aggregate_exact(
+    out: &mut dyn Write,
+    networks: &[String],
+    format: OutputFormat,
+    width: Option<usize>,
+    quiet: bool,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    let (inputs, aggregated) = if networks.iter().any(|arg| arg.contains(':')) {
+        reject_ipv6_records(format)?;
+        let parsed_networks = parse_ip_networks(networks, default_prefix)?;
+        let aggregated = IpNetwork::aggregate_exact_by_family(&parsed_networks)?;
+        if format == OutputFormat::Json {
+            let records: Vec<_> = aggregated.iter().map(IpNetwork::describe).collect();
+            return print_json(out, &records);
+        }
+        (parsed_networks.len(), aggregated)
+    } else {
+        let parsed_networks: Vec<Network> = parse_networks(networks, default_prefix)?;
+        let aggregated = Network::aggregate_exact(&parsed_networks)?;
+        if format != OutputFormat::Text {
+            let infos: Vec<NetworkInfo> = aggregated.iter().map(Network::describe).collect();
+            return print_records(out, &infos, format, width);
+        }
+        let aggregated = aggregated.into_iter().map(IpNetwork::V4).collect();
+        (parsed_networks.len(), aggregated)
+    };
+    writeln!(out, "{}:", theme::label("Aggregated Networks"))?;
+    for network in &aggregated {
+        writeln!(out, "  {}", theme::network(&network.to_string()))?;
+    }
+    if !quiet {
+        writeln!(
+            out,
+            "combined {} into {}",
+            count_networks(inputs),
+            aggregated.len()
+        )?;
+    }
+    Ok(())
+}
+
+/// IPv6 records have no CSV or table layout, only JSON and the labelled text.
+fn reject_ipv6_records(format: OutputFormat) -> Result<(), CliError> {
+    match format {
+        OutputFormat::Csv | OutputFormat::Table => Err(CliError::Format(
+            "IPv6 networks have no csv or table layout, use json".to_string(),
+        )),
+        OutputFormat::Text | OutputFormat::Json => Ok(()),
+    }
+}
+
+/// Aggregates a list containing IPv6 networks, each family on its own, IPv4 first.
+/// The prefix floor and the CSV and table layouts only exist for IPv4, so they are refused.
+fn handle_dual_stack_aggregate(
+    out: &mut dyn Write,
+    networks: &[String],
+    format: OutputFormat,
+    options: AggregateOptions,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    if options.min_prefix.is_some() {
+        return Err(CliError::Format(
+            "--min-prefix only applies to IPv4 networks".to_string(),
+        ));
+    }
+    reject_ipv6_records(format)?;
+    let parsed_networks = parse_ip_networks(networks, default_prefix)?;
+    let aggregated = IpNetwork::aggregate_by_family(&parsed_networks)?;
+    if format == OutputFormat::Json {
+        let records: Vec<_> = aggregated.iter().map(IpNetwork::describe).collect();
+        return print_json(out, &records);
+    }
+    for aggregated_network in &aggregated {
+        writeln!(
+            out,
+            "{}: {}",
+            theme::label("Aggregated Network"),
+            theme::network(&aggregated_network.to_string())
+        )?;
+        let members: Vec<IpNetwork> = parsed_networks
+            .iter()
+            .filter(|network| network.is_ipv4() == aggregated_network.is_ipv4())
+            .copied()
+            .collect();
+        if options.show_members {
+            display_members(out, &members, |member| {
+                aggregated_network.contains_network(member)
+            })?;
+        }
+        if options.quiet {
+            continue;
+        }
+        if let IpNetwork::V4(network) = aggregated_network {
+            warn_classful_span(network);
+        }
+        write_aggregate_note(out, members.len(), aggregated_network.prefix())?;
+    }
+    if options.verify {
+        write_verification(out, &aggregated, &parsed_networks)?;
+    }
+    Ok(())
+}
+
+/// Warns on stderr when the aggregate covers several legacy class blocks, e.g. `10.0.0.0/7`.
+fn warn_classful_span(aggregated_network: &Network) {
+    if !aggregated_network.spans_classful_boundary() {
+        return;
+    }
+    let classful = Network::default_mask(aggregated_network.network_address());
+    eprintln!(
+        "{}: {} is shorter than the classful /{} and spans {} class {} blocks",
+        theme::hint("warning").bold(),
+        aggregated_network,
+        classful,
+        1u64 << (classful - aggregated_network.prefix),
+        aggregated_network.ip_class()
+    );
+}
+
+/// The trailing `combined N networks into 1` note of `aggregate`.
+fn write_aggregate_note(out: &mut dyn Write, members: usize, prefix: u32) -> std::io::Result<()> {
+    let saved = members.saturating_sub(1);
+    writeln!(
+        out,
+        "combined {} into 1 (/{}), saving {} table {}",
+        count_networks(members),
+        prefix,
+        saved,
+        if saved == 1 { "entry" } else { "entries" }
+    )
+}
+
+/// The `summarize` flags besides the output format.
+#[derive(Clone, Copy, Default)]
+struct SummarizeOptions {
+    quiet: bool,
+    summary_only: bool,
+}
+
+fn handle_summarize(
+    out: &mut dyn Write,
+    networks: &[String],
+    format: OutputFormat,
+    width: Option<usize>,
+    options: SummarizeOptions,
+    delimiter: Option<char>,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    let parsed_networks: Vec<Network> = parse_networks(networks, default_prefix)?;
+    let summarized = Network::summarize_networks(&parsed_networks);
+    if options.summary_only {
+        // Plain numbers, so monitoring scripts can read them without stripping separators
+        let addresses: u64 = summarized.iter().map(Network::address_count).sum();
+        writeln!(out, "{}: {}", theme::label("Blocks"), summarized.len())?;
+        writeln!(out, "{}: {}", theme::label("Addresses"), addresses)?;
+        return Ok(());
+    }
+    if format != OutputFormat::Text {
+        let infos: Vec<NetworkInfo> = summarized.iter().map(Network::describe).collect();
+        return print_records(out, &infos, format, width);
+    }
+    if delimiter.is_some() {
+        return Ok(write_delimited(out, &summarized, delimiter)?);
+    }
+    writeln!(out, "{}:", theme::label("Summarized Networks"))?;
+    for network in &summarized {
+        writeln!(out, "  {}", theme::network(&network.to_string()))?;
+    }
+    if !options.quiet {
+        writeln!(
+            out,
+            "combined {} into {}",
+            count_networks(parsed_networks.len()),
+            summarized.len()
+        )?;
+    }
+    Ok(())
+}
+
+fn handle_hosts(
+    out: &mut dyn Write,
+    network: &str,
+    count_only: bool,
+    delimiter: Option<char>,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    let network = parse_network(network, default_prefix)?;
+    if count_only {
+        writeln!(out, "{}", network.available_hosts())?;
+        return Ok(());
+    }
+    // Written as the iterator yields them, so large networks stream instead of buffering
+    Ok(write_delimited(out, network.hosts(), delimiter)?)
+}
+
+fn handle_expand(
+    out: &mut dyn Write,
+    network: &str,
+    force: bool,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    let network = parse_network(network, default_prefix)?;
+    if force {
+        for ip in network.addresses() {
+            writeln!(out, "{}", ip)?;
+        }
+        return Ok(());
+    }
+    for ip in network.expand()? {
+        writeln!(out, "{}", ip)?;
+    }
+    Ok(())
+}
+
+fn handle_split(
+    out: &mut dyn Write,
+    network: &str,
+    into: u32,
+    ascii: bool,
+    warn_unaligned: bool,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    let parent = parse_network(network, default_prefix)?;
+    let subnets = parent.split(into)?;
+    if ascii {
+        write!(out, "{}", Network::render_division(&parent, &subnets))?;
+        return Ok(());
+    }
+    writeln!(out, "{}:", "Subnets".bold().green())?;
+    for subnet in &subnets {
+        if warn_unaligned && subnet.crosses_octet_boundary() {
+            writeln!(
+                out,
+                "  {} {}",
+                subnet.to_string().purple(),
+                format!("(boundary inside octet {})", subnet.prefix / 8 + 1).yellow()
+            )?;
+        } else {
+            writeln!(out, "  {}", subnet.to_string().purple())?;
+        }
+    }
+    Ok(())
+}
+
+fn handle_info(
+    out: &mut dyn Write,
+    network_str: &str,
+    format: OutputFormat,
+    width: Option<usize>,
+    view: InfoView,
+    policy: Option<HostCountPolicy>,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    if let Some(digits) = bare_prefix(network_str) {
+        let prefix = digits
+            .parse::<u32>()
+            .ok()
+            .filter(|prefix| *prefix <= 32)
+            .ok_or(NetworkError::InvalidPrefixLength)?;
+        // A lone prefix has no address to fill records or a summary line with
+        if format != OutputFormat::Text {
+            return Err(CliError::Format(
+                "a bare prefix only reports as text".to_string(),
+            ));
+        }
+        return match view {
+            InfoView::PrefixOnly => Ok(writeln!(out, "{}", prefix)?),
+            InfoView::Full => Ok(display_prefix_info(
+                out,
+                prefix,
+                policy.unwrap_or_default(),
+            )?),
+            InfoView::Quiet | InfoView::Oneline => Err(CliError::Format(
+                "a bare prefix only supports the full and --prefix-only views".to_string(),
+            )),
+        };
+    }
+    if network_str.contains(':') {
+        if policy.is_some() {
+            return Err(CliError::Format(
+                "--policy only applies to IPv4, IPv6 counts every address".to_string(),
+            ));
+        }
+        return handle_ipv6_info(out, &network_str.parse()?, format, view);
+    }
+    let network = parse_network(network_str, default_prefix)?;
+    let info = NetworkInfo::new(&network, policy.unwrap_or_default());
+    match view {
+        InfoView::PrefixOnly => writeln!(out, "{}", network.prefix)?,
+        InfoView::Oneline => writeln!(out, "{}", network.summary_line())?,
+        InfoView::Quiet => {
+            // Same order as the labelled output
+            writeln!(out, "{}", network)?;
+            writeln!(out, "{}", info.netmask)?;
+            writeln!(out, "{}", info.wildcard)?;
+            writeln!(out, "{}", broadcast_field(&info))?;
+            writeln!(out, "{}", info.available_hosts)?;
+            writeln!(out, "{}", info.class)?;
+        }
+        InfoView::Full => match format {
+            OutputFormat::Text => display_network_info(out, &network, info.available_hosts)?,
+            OutputFormat::Json => print_json(out, &info)?,
+            OutputFormat::Csv | OutputFormat::Table => print_records(out, &[info], format, width)?,
+        },
+    }
+    Ok(())
+}
+
+/// Counts too large for the usual digits are shown as a power of two, e.g. `2^80`.
+fn handle_ipv6_info(
+    out: &mut dyn Write,
+    network: &Ipv6Network,
+    format: OutputFormat,
+    view: InfoView,
+) -> Result<(), CliError> {
+    reject_ipv6_records(format)?;
+    let info = network.describe();
+    let hosts = match u64::try_from(info.available_hosts) {
+        Ok(hosts) if network.prefix > 0 => display_count(hosts),
+        _ => format!("2^{}", 128 - network.prefix),
+    };
+    match view {
+        InfoView::PrefixOnly => writeln!(out, "{}", network.prefix)?,
+        InfoView::Oneline => writeln!(out, "{}/{} ({} hosts)", info.network, info.prefix, hosts)?,
+        InfoView::Quiet => {
+            writeln!(out, "{}", network)?;
+            writeln!(out, "{}", info.netmask)?;
+            writeln!(out, "{}", info.last_address)?;
+            writeln!(out, "{}", hosts)?;
+        }
+        InfoView::Full if format == OutputFormat::Json => print_json(out, &info)?,
+        InfoView::Full => {
+            writeln!(
+                out,
+                "{}: {}",
+                theme::label("Network"),
+                theme::network(&network.to_string())
+            )?;
+            writeln!(
+                out,
+                "{}: {}",
+                theme::label("Netmask"),
+                theme::value(&info.netmask.to_string())
+            )?;
+            writeln!(
+                out,
+                "{}: {}",
+                theme::label("Last Address"),
+                theme::value(&info.last_address.to_string())
+            )?;
+            writeln!(
+                out,
+                "{}: {}",
+                theme::label("Available Hosts"),
+                theme::value(&hosts)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn handle_validate(
+    out: &mut dyn Write,
+    tokens: &[String],
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    let mut invalid = 0;
+    for token in tokens {
+        match parse_network(token, default_prefix) {
+            Ok(_) => writeln!(out, "{}: {}", token, "valid".bold().green())?,
+            Err(e) => {
+                invalid += 1;
+                writeln!(
+                    out,
+                    "{}: {} ({})",
+                    token,
+                    "invalid".bold().red(),
+                    e.to_string().red()
+                )?;
+            }
+        }
+    }
+    if invalid > 0 {
+        return Err(CliError::Format(format!(
+            "{} of {} networks are invalid",
+            invalid,
+            tokens.len()
+        )));
+    }
+    Ok(())
+}
+
+fn handle_filter(
+    out: &mut dyn Write,
+    network: &str,
+    ips: &[String],
+    invert: bool,
+    delimiter: Option<char>,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    let network = parse_network(network, default_prefix)?;
+    let tokens = if ips.is_empty() {
+        std::io::stdin()
+            .lines()
+            .collect::<Result<Vec<_>, _>>()?
+            .iter()
+            .flat_map(|line| split_network_list(line))
+            .collect()
+    } else {
+        ips.iter()
+            .flat_map(|arg| split_network_list(arg))
+            .collect::<Vec<_>>()
+    };
+    let ips = tokens
+        .iter()
+        .map(|token| token.parse::<Ipv4Addr>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(NetworkError::from)?;
+    let matching = ips.into_iter().filter(|ip| network.contains(*ip) != invert);
+    Ok(write_delimited(out, matching, delimiter)?)
+}
+
+fn handle_compare(
+    out: &mut dyn Write,
+    network_a: &str,
+    network_b: &str,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    let a = parse_network(network_a, default_prefix)?;
+    let b = parse_network(network_b, default_prefix)?;
+
+    // CIDR blocks can't partially overlap, overlapping ones are always nested
+    let relationship = if !a.overlaps(&b) {
+        format!("{} and {} are disjoint", a, b)
+    } else if a.contains_network(&b) && b.contains_network(&a) {
+        format!("{} equals {}", a, b)
+    } else if a.contains_network(&b) {
+        format!("{} contains {}", a, b)
+    } else {
+        format!("{} contains {}", b, a)
+    };
+    let size_difference = a.address_count().abs_diff(b.address_count());
+
+    writeln!(
+        out,
+        "{}: {}",
+        "Relationship".bold().green(),
+        relationship.purple()
+    )?;
+    writeln!(
+        out,
+        "{}: {} addresses",
+        "Size Difference".bold().green(),
+        size_difference.to_string().yellow()
+    )?;
+    Ok(())
+}
+
+fn handle_diff(
+    out: &mut dyn Write,
+    old: &Path,
+    new: &Path,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    let old_networks = read_networks_file(old, default_prefix)?;
+    let new_networks = read_networks_file(new, default_prefix)?;
+    let (added, removed) = Network::diff(&old_networks, &new_networks);
+    for network in &added {
+        writeln!(out, "{}", format!("+ {}", network).green())?;
+    }
+    for network in &removed {
+        writeln!(out, "{}", format!("- {}", network).red())?;
+    }
+    Ok(())
+}
+
+fn handle_plan(
+    out: &mut dyn Write,
+    parent: &str,
+    demands_file: &Path,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    let parent = parse_network(parent, default_prefix)?;
+    let demands = read_list_file(demands_file)?
+        .iter()
+        .map(|line| line.parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let assigned = allocate_or_report(&parent, &demands)?;
+    for (subnet, demand) in assigned.iter().zip(&demands) {
+        writeln!(
+            out,
+            "{} ({} hosts)",
+            theme::network(&subnet.to_string()),
+            demand
+        )?;
+    }
+    Ok(())
+}
+
+fn handle_report(
+    out: &mut dyn Write,
+    parent: &str,
+    demands: &[u32],
+    html: bool,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    let parent = parse_network(parent, default_prefix)?;
+    let assigned = allocate_or_report(&parent, demands)?;
+    if html {
+        write!(out, "{}", Network::render_html(&parent, &assigned))?;
+    } else {
+        write!(out, "{}", Network::render_division(&parent, &assigned))?;
+    }
+    Ok(())
+}
+
+/// Allocates every demand from `parent`, largest first so the parent packs best, and returns
+/// the subnets in demand order, or an error naming the first demand that did not fit.
+fn allocate_or_report(parent: &Network, demands: &[u32]) -> Result<Vec<Network>, CliError> {
+    let mut order: Vec<usize> = (0..demands.len()).collect();
+    order.sort_by_key(|&i| Reverse(demands[i]));
+    let mut allocator = VlsmAllocator::new(*parent);
+    let mut assigned = vec![None; demands.len()];
+    for i in order {
+        let subnet = allocator
+            .allocate(demands[i])
+            .map_err(|e| CliError::Unfit(demands[i], *parent, e))?;
+        assigned[i] = Some(subnet);
+    }
+    Ok(assigned.into_iter().flatten().collect())
+}
+
+fn handle_allocate(
+    out: &mut dyn Write,
+    parent: &str,
+    prefix: u32,
+    used: &Path,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    let parent = parse_network(parent, default_prefix)?;
+    let used = read_networks_file(used, default_prefix)?;
+    let subnet = Network::first_free(&parent, &used, prefix)?;
+    writeln!(out, "{}", theme::network(&subnet.to_string()))?;
+    Ok(())
+}
+
+fn handle_fit(out: &mut dyn Write, hosts: u32) -> Result<(), CliError> {
+    let prefix = Network::prefix_for_hosts(hosts)?;
+    let subnet = Network::new(Ipv4Addr::UNSPECIFIED, prefix);
+    let usable = subnet.available_hosts();
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Prefix"),
+        theme::value(&format!("/{}", prefix))
+    )?;
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Netmask"),
+        theme::value(&subnet.netmask_address().to_string())
+    )?;
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Usable Hosts"),
+        theme::value(&display_count(usable.into()))
+    )?;
+    // Usable addresses beyond the requested hosts
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Wasted Addresses"),
+        theme::value(&display_count(u64::from(usable.saturating_sub(hosts))))
+    )?;
+    Ok(())
+}
+
+fn handle_examples(
+    out: &mut dyn Write,
+    only: Option<Example>,
+    width: Option<usize>,
+) -> Result<(), CliError> {
+    let selected = match only {
+        Some(example) => vec![example],
+        None => Example::value_variants().to_vec(),
+    };
+    for (i, example) in selected.into_iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        let name = example.to_possible_value().unwrap();
+        let args = example.args();
+        writeln!(
+            out,
+            "{}",
+            theme::hint(&format!(
+                "$ subnetcalc {} {}",
+                name.get_name(),
+                args.join(" ")
+            ))
+        )?;
+        // Run the real handlers so the examples always match the current behavior
+        let networks: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        match example {
+            Example::Info => handle_info(
+                out,
+                args[0],
+                OutputFormat::Text,
+                width,
+                InfoView::Full,
+                None,
+                None,
+            )?,
+            Example::Aggregate => handle_aggregate(
+                out,
+                &networks,
+                OutputFormat::Text,
+                width,
+                AggregateOptions::default(),
+                None,
+            )?,
+            Example::Summarize => handle_summarize(
+                out,
+                &networks,
+                OutputFormat::Text,
+                width,
+                SummarizeOptions::default(),
+                None,
+                None,
+            )?,
+            Example::Split => handle_split(out, args[0], args[2].parse()?, false, false, None)?,
+            Example::Mask => handle_mask(
+                out,
+                args[0],
+                args[1].parse()?,
+                args[2].parse()?,
+                OutputFormat::Text,
+                false,
+                None,
+            )?,
+        }
+    }
+    Ok(())
+}
+
+fn handle_reference(out: &mut dyn Write, width: Option<usize>) -> Result<(), CliError> {
+    let header = format!(
+        "{:<7} {:<16} {:<16} {:>10} {:>12}",
+        "Prefix", "Netmask", "Wildcard", "Addresses", "Usable Hosts"
+    );
+    writeln!(out, "{}", fit_width(&header, width).bold().green())?;
+    for prefix in 0..=32 {
+        let network = Network::new(Ipv4Addr::UNSPECIFIED, prefix);
+        let row = format!(
+            "{:<7} {:<16} {:<16} {:>10} {:>12}",
+            format!("/{}", prefix),
+            network.netmask_address(),
+            network.wildcard_address(),
+            network.address_count(),
+            network.available_hosts()
+        );
+        writeln!(out, "{}", fit_width(&row, width))?;
+    }
+    Ok(())
+}
+
+fn handle_mask(
+    out: &mut dyn Write,
+    network: &str,
+    required_subnets: u32,
+    required_hosts: u32,
+    format: OutputFormat,
+    quiet: bool,
+    default_prefix: Option<u32>,
+) -> Result<(), CliError> {
+    let parsed_network = parse_network(network, default_prefix)?;
+    let plan = SubnetPlan::builder()
+        .base_prefix(parsed_network.prefix)
+        .subnets(required_subnets)
+        .hosts(required_hosts)
+        .solve()?;
+    if format == OutputFormat::Json {
+        return print_json(out, &plan);
+    }
+    if quiet {
+        writeln!(out, "{}", plan.netmask)?;
+        return Ok(());
+    }
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Subnet Mask"),
+        theme::value(&plan.netmask.to_string())
+    )?;
+    Ok(())
+}
+
+/// Parses a network from the command line, honouring `--default-prefix`.
+fn parse_network(network: &str, default_prefix: Option<u32>) -> Result<Network, NetworkError> {
+    Network::parse_with_default(network, default_prefix)
+}
+
+/// A bare `/26` or `26` asks about the prefix itself rather than a particular network.
+fn bare_prefix(token: &str) -> Option<&str> {
+    let token = token.trim();
+    let digits = token.strip_prefix('/').unwrap_or(token);
+    (!digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())).then_some(digits)
+}
+
+/// Like `parse_network`, but an address with a `:` is read as IPv6.
+fn parse_ip_network(network: &str, default_prefix: Option<u32>) -> Result<IpNetwork, NetworkError> {
+    if network.contains(':') {
+        network.parse().map(IpNetwork::V6)
+    } else {
+        parse_network(network, default_prefix).map(IpNetwork::V4)
+    }
+}
+
+/// Like `parse_networks`, reading each entry with `parse_ip_network`.
+fn parse_ip_networks(
+    networks: &[String],
+    default_prefix: Option<u32>,
+) -> Result<Vec<IpNetwork>, CliError> {
+    networks
+        .iter()
+        .flat_map(|arg| split_network_list(arg))
+        .map(|entry| {
+            parse_ip_network(&entry, default_prefix).map_err(|e| CliError::Entry(entry, e))
+        })
+        .collect()
+}
+
+/// Each argument may itself hold several networks separated by commas or whitespace.
+fn parse_networks(
+    networks: &[String],
+    default_prefix: Option<u32>,
+) -> Result<Vec<Network>, CliError> {
+    let entries: Vec<String> = networks
+        .iter()
+        .flat_map(|arg| split_network_list(arg))
+        .collect();
+    Network::parse_list_with_default(&entries, default_prefix)
+        .map_err(|(index, e)| CliError::Entry(entries[index].clone(), e))
+}
+
+/// Splits `"a,b c"` into tokens while keeping a spaced slash like `"10.0.0.0 / 8"` together.
+fn split_network_list(arg: &str) -> Vec<String> {
+    arg.split(',')
+        .flat_map(|item| {
+            let joined = item.split('/').map(str::trim).collect::<Vec<_>>().join("/");
+            joined
+                .split_whitespace()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Reads one network per line, skipping blank lines and `#` comments.
+fn read_networks_file(path: &Path, default_prefix: Option<u32>) -> Result<Vec<Network>, CliError> {
+    parse_networks(&read_list_file(path)?, default_prefix)
+}
+
+/// Reads the non-blank lines of a file, skipping `#` comments.
+fn read_list_file(path: &Path) -> std::io::Result<Vec<String>> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+/// A /31 has no broadcast address, shown as `none` in the plain and tabular output.
+fn broadcast_field(info: &NetworkInfo) -> String {
+    info.broadcast
+        .map_or_else(|| "none".to_string(), |broadcast| broadcast.to_string())
+}
+
+/// Prints the records in a structured format, text callers normally render their own
+/// labelled output so it falls back to the table.
+fn print_records(
+    out: &mut dyn Write,
+    infos: &[NetworkInfo],
+    format: OutputFormat,
+    width: Option<usize>,
+) -> Result<(), CliError> {
+    const HEADERS: [&str; 7] = [
+        "network",
+        "prefix",
+        "netmask",
+        "wildcard",
+        "broadcast",
+        "available_hosts",
+        "class",
+    ];
+    let rows = infos.iter().map(|info| {
+        [
+            info.network.to_string(),
+            info.prefix.to_string(),
+            info.netmask.to_string(),
+            info.wildcard.to_string(),
+            broadcast_field(info),
+            info.available_hosts.to_string(),
+            info.class.to_string(),
+        ]
+    });
+
+    match format {
+        OutputFormat::Json => print_json(out, infos)?,
+        OutputFormat::Csv => {
+            writeln!(out, "{}", HEADERS.join(","))?;
+            for row in rows {
+                writeln!(out, "{}", row.join(","))?;
+            }
+        }
+        OutputFormat::Text | OutputFormat::Table => {
+            let header = format!(
+                "{:<16} {:<6} {:<16} {:<16} {:<16} {:>15} {}",
+                "Network", "Prefix", "Netmask", "Wildcard", "Broadcast", "Available Hosts", "Class"
+            );
+            writeln!(out, "{}", fit_width(&header, width).bold().green())?;
+            for [network, prefix, netmask, wildcard, broadcast, hosts, class] in rows {
+                let row = format!(
+                    "{:<16} {:<6} {:<16} {:<16} {:<16} {:>15} {}",
+                    network, prefix, netmask, wildcard, broadcast, hosts, class
+                );
+                writeln!(out, "{}", fit_width(&row, width))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes one value per line, or separated by `delimiter` when given. A comma or tab list ends
+/// with a newline, a `\0` list terminates every value with `\0` like `find -print0`.
+fn write_delimited<T: std::fmt::Display>(
+    out: &mut dyn Write,
+    items: impl IntoIterator<Item = T>,
+    delimiter: Option<char>,
+) -> std::io::Result<()> {
+    let delimiter = delimiter.unwrap_or('\n');
+    let terminator = if delimiter == '\0' { '\0' } else { '\n' };
+    let mut any = false;
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            write!(out, "{}", delimiter)?;
+        }
+        write!(out, "{}", item)?;
+        any = true;
+    }
+    if any {
+        write!(out, "{}", terminator)?;
+    }
+    Ok(())
+}
+
+/// Reads `--delimiter`, accepting a single character or the escapes `\t` and `\0`.
+fn parse_delimiter(value: &str) -> Result<char, String> {
+    match value {
+        "\\t" => Ok('\t'),
+        "\\0" => Ok('\0'),
+        _ => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(format!("expected a single character, got `{}`", value)),
+            }
+        }
+    }
+}
+
+/// Cuts a table line to `width` columns, marking the cut with `…`.
+fn fit_width(line: &str, width: Option<usize>) -> String {
+    let line = line.trim_end();
+    match width {
+        Some(width) if line.chars().count() > width => {
+            let mut cut: String = line.chars().take(width.saturating_sub(1)).collect();
+            cut.push('…');
+            cut
+        }
+        _ => line.to_string(),
+    }
+}
+
+/// The terminal width from `COLUMNS` or 80, `None` when stdout is not a terminal so
+/// piped tables are never cut.
+fn terminal_width() -> Option<usize> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let columns = std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok());
+    Some(columns.unwrap_or(80))
+}
+
+fn print_json<T: Serialize + ?Sized>(out: &mut dyn Write, value: &T) -> Result<(), CliError> {
+    writeln!(out, "{}", serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+/// Lists the inputs under an aggregate, flagging those `contained` rejects.
+fn display_members<T: std::fmt::Display>(
+    out: &mut dyn Write,
+    members: &[T],
+    contained: impl Fn(&T) -> bool,
+) -> std::io::Result<()> {
+    for member in members {
+        if contained(member) {
+            writeln!(out, "  {}", theme::network(&member.to_string()))?;
+        } else {
+            writeln!(
+                out,
+                "  {} {}",
+                theme::network(&member.to_string()),
+                theme::error("(not contained)").bold()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn count_networks(count: usize) -> String {
+    match count {
+        1 => String::from("1 network"),
+        _ => format!("{} networks", count),
+    }
+}
+
+fn display_count(count: u64) -> String {
+    if count >= 1_000_000 {
+        format!(
+            "{} ({})",
+            utils::humanize_count(count),
+            utils::approximate_count(count)
+        )
+    } else {
+        utils::humanize_count(count)
+    }
+}
+
+/// Like `display_count`, counts past u64 from IPv6 show as a power of two when they are one.
+fn display_wide_count(count: u128) -> String {
+    match u64::try_from(count) {
+        Ok(count) => display_count(count),
+        Err(_) if count.is_power_of_two() => format!("2^{}", count.trailing_zeros()),
+        Err(_) => count.to_string(),
+    }
+}
+
+/// The mask math of a prefix, without the network-specific addresses.
+fn display_prefix_info(
+    out: &mut dyn Write,
+    prefix: u32,
+    policy: HostCountPolicy,
+) -> std::io::Result<()> {
+    let network = Network::new(Ipv4Addr::UNSPECIFIED, prefix);
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Netmask"),
+        theme::value(&network.netmask_address().to_string())
+    )?;
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Wildcard"),
+        theme::value(&network.wildcard_address().to_string())
+    )?;
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Addresses"),
+        theme::value(&display_count(network.address_count()))
+    )?;
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Available Hosts"),
+        theme::value(&display_count(network.usable_hosts_with(policy)))
+    )
+}
+
+fn display_network_info(
+    out: &mut dyn Write,
+    network: &Network,
+    available_hosts: u64,
+) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Network"),
+        theme::network(&network.to_string())
+    )?;
+    if !network.is_aligned() {
+        writeln!(
+            out,
+            "{}",
+            theme::hint(&format!(
+                "did you mean {}/{}?",
+                network.network_address(),
+                network.prefix
+            ))
+        )?;
+    }
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Netmask"),
+        theme::value(&network.netmask_address().to_string())
+    )?;
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Wildcard"),
+        theme::value(&network.wildcard_address().to_string())
+    )?;
+    if network.is_point_to_point() {
+        writeln!(
+            out,
+            "{}: {}",
+            theme::label("Broadcast"),
+            theme::value("none (point-to-point)")
+        )?;
+    } else {
+        writeln!(
+            out,
+            "{}: {}",
+            theme::label("Broadcast"),
+            theme::value(&network.broadcast_address().to_string())
+        )?;
+    }
+    writeln!(
+        out,
+        "{}: {}",
+        theme::label("Available Hosts"),
+        theme::value(&display_count(available_hosts))
+    )?;
+    match network.special_class_note() {
+        Some(note) => writeln!(
+            out,
+            "{}: {} ({})",
+            theme::label("Class"),
+            theme::class(&network.ip_class().to_string()),
+            theme::class(note)
+        )?,
+        None => writeln!(
+            out,
+            "{}: {}",
+            theme::label("Class"),
+            theme::class(&network.ip_class().to_string())
+        )?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture(handler: impl FnOnce(&mut dyn Write) -> Result<(), CliError>) -> String {
+        colored::control::set_override(false);
+        let mut buffer = Vec::new();
+        handler(&mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_handle_info_writes_to_buffer() {
+        let text = capture(|out| {
+            handle_info(
+                out,
+                "192.168.1.0/26",
+                OutputFormat::Text,
+                None,
+                InfoView::Full,
+                None,
+                None,
+            )
+        });
+        assert!(text.lines().any(|line| line == "Broadcast: 192.168.1.63"));
+    }
+
+    #[test]
+    fn test_handlers_use_default_prefix() {
+        let text = capture(|out| {
+            handle_info(
+                out,
+                "192.168.1.0",
+                OutputFormat::Text,
+                None,
+                InfoView::PrefixOnly,
+                None,
+                Some(26),
+            )
+        });
+        assert_eq!(text, "26\n");
+
+        let networks = ["10.0.0.0".to_string(), "10.0.1.0".to_string()];
+        let text = capture(|out| {
+            let options = AggregateOptions {
+                quiet: true,
+                ..AggregateOptions::default()
+            };
+            handle_aggregate(out, &networks, OutputFormat::Text, None, options, Some(24))
+        });
+        assert_eq!(text, "Aggregated Network: 10.0.0.0/23\n");
+    }
+
+    #[test]
+    fn test_handle_aggregate_and_mask_write_to_buffer() {
+        let networks = ["10.0.0.0/24".to_string(), "10.0.1.0/24".to_string()];
+        let text = capture(|out| {
+            let options = AggregateOptions {
+                quiet: true,
+                ..AggregateOptions::default()
+            };
+            handle_aggregate(out, &networks, OutputFormat::Text, None, options, None)
+        });
+        assert_eq!(text, "Aggregated Network: 10.0.0.0/23\n");
+
+        let text =
+            capture(|out| handle_mask(out, "172.16.0.0", 90, 350, OutputFormat::Text, false, None));
+        assert_eq!(text, "Subnet Mask: 255.255.254.0\n");
+    }
+}
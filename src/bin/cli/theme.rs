@@ -0,0 +1,82 @@
+use clap::ValueEnum;
+use colored::*;
+use std::sync::OnceLock;
+
+/// Colour palettes for terminal output, `Mono` disables colours entirely.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Theme {
+    /// Bright colours for dark backgrounds
+    #[default]
+    Dark,
+    /// Darker colours that stay readable on light backgrounds
+    Light,
+    /// No colours
+    Mono,
+}
+
+static CURRENT: OnceLock<Theme> = OnceLock::new();
+
+impl Theme {
+    /// Selects the palette used by the helpers below for the rest of the process.
+    pub fn install(self) {
+        if self == Theme::Mono {
+            colored::control::set_override(false);
+        }
+        let _ = CURRENT.set(self);
+    }
+
+    fn current() -> Theme {
+        CURRENT.get().copied().unwrap_or_default()
+    }
+}
+
+/// Field names such as `Netmask` in labelled output.
+pub fn label(text: &str) -> ColoredString {
+    match Theme::current() {
+        Theme::Dark => text.bold().green(),
+        Theme::Light => text.bold().blue(),
+        Theme::Mono => text.normal(),
+    }
+}
+
+pub fn network(text: &str) -> ColoredString {
+    match Theme::current() {
+        Theme::Dark => text.purple(),
+        Theme::Light => text.magenta(),
+        Theme::Mono => text.normal(),
+    }
+}
+
+/// Addresses, masks and counts.
+pub fn value(text: &str) -> ColoredString {
+    match Theme::current() {
+        Theme::Dark => text.yellow(),
+        Theme::Light => text.black(),
+        Theme::Mono => text.normal(),
+    }
+}
+
+/// The address class and its note.
+pub fn class(text: &str) -> ColoredString {
+    match Theme::current() {
+        Theme::Dark => text.cyan(),
+        Theme::Light => text.blue(),
+        Theme::Mono => text.normal(),
+    }
+}
+
+/// Hints such as "did you mean", which should stand out without looking like errors.
+pub fn hint(text: &str) -> ColoredString {
+    match Theme::current() {
+        Theme::Dark => text.yellow(),
+        Theme::Light => text.red(),
+        Theme::Mono => text.normal(),
+    }
+}
+
+pub fn error(text: &str) -> ColoredString {
+    match Theme::current() {
+        Theme::Dark | Theme::Light => text.bold().red(),
+        Theme::Mono => text.normal(),
+    }
+}
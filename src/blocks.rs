@@ -0,0 +1,192 @@
+use crate::errors::NetworkError;
+use crate::subnet::{Ipv6Network, Network};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ops::{BitAnd, BitOr};
+
+/// The integer behind an address, `u32` for IPv4 and `u128` for IPv6.
+pub(crate) trait AddressBits:
+    Copy + Ord + BitAnd<Output = Self> + BitOr<Output = Self>
+{
+    fn mask(prefix: u32) -> Self;
+    /// Number of addresses in a block of `prefix`, which must be at least 1.
+    fn block_size(prefix: u32) -> Self;
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+impl AddressBits for u32 {
+    fn mask(prefix: u32) -> Self {
+        Network::mask_to_u32(prefix)
+    }
+
+    fn block_size(prefix: u32) -> Self {
+        1 << (32 - prefix)
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        u32::checked_add(self, other)
+    }
+}
+
+impl AddressBits for u128 {
+    fn mask(prefix: u32) -> Self {
+        Ipv6Network::mask_to_u128(prefix)
+    }
+
+    fn block_size(prefix: u32) -> Self {
+        1 << (128 - prefix)
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        u128::checked_add(self, other)
+    }
+}
+
+/// A network of either family seen as its address bits and prefix length, so the block
+/// arithmetic below is written once.
+pub(crate) trait Cidr: Copy {
+    type Bits: AddressBits;
+
+    fn to_block(&self) -> (Self::Bits, u32);
+    fn from_block(bits: Self::Bits, prefix: u32) -> Self;
+    fn validate(&self) -> Result<(), NetworkError>;
+}
+
+impl Cidr for Network {
+    type Bits = u32;
+
+    fn to_block(&self) -> (u32, u32) {
+        (u32::from(self.ip), self.prefix)
+    }
+
+    fn from_block(bits: u32, prefix: u32) -> Self {
+        Network::new(Ipv4Addr::from(bits), prefix)
+    }
+
+    fn validate(&self) -> Result<(), NetworkError> {
+        Network::validate(self)
+    }
+}
+
+impl Cidr for Ipv6Network {
+    type Bits = u128;
+
+    fn to_block(&self) -> (u128, u32) {
+        (u128::from(self.ip), self.prefix)
+    }
+
+    fn from_block(bits: u128, prefix: u32) -> Self {
+        Ipv6Network::new(Ipv6Addr::from(bits), prefix)
+    }
+
+    fn validate(&self) -> Result<(), NetworkError> {
+        Ipv6Network::validate(self)
+    }
+}
+
+type Block<W> = (W, u32);
+
+/// The block with its host bits cleared.
+fn normalized<N: Cidr>(network: &N) -> Block<N::Bits> {
+    let (bits, prefix) = network.to_block();
+    (bits & N::Bits::mask(prefix), prefix)
+}
+
+fn contains<W: AddressBits>(outer: Block<W>, inner: Block<W>) -> bool {
+    let mask = W::mask(outer.1);
+    outer.1 <= inner.1 && inner.0 & mask == outer.0 & mask
+}
+
+fn overlaps<W: AddressBits>(a: Block<W>, b: Block<W>) -> bool {
+    contains(a, b) || contains(b, a)
+}
+
+/// The parent of `a` and `b` when they are the two aligned halves of it, in that order.
+pub(crate) fn merge_siblings<N: Cidr>(a: &N, b: &N) -> Option<N> {
+    let ((a_bits, prefix), (b_bits, b_prefix)) = (a.to_block(), b.to_block());
+    if prefix != b_prefix || prefix == 0 {
+        return None;
+    }
+    // `a` must be the lower half, i.e. aligned to the parent
+    if a_bits & N::Bits::mask(prefix - 1) != a_bits
+        || a_bits.checked_add(N::Bits::block_size(prefix)) != Some(b_bits)
+    {
+        return None;
+    }
+    Some(N::from_block(a_bits, prefix - 1))
+}
+
+/// Backs `summarize_networks` for both families, the result is sorted by address.
+pub(crate) fn summarize<N: Cidr>(networks: &[N]) -> Vec<N> {
+    let mut sorted: Vec<Block<N::Bits>> = networks.iter().map(normalized).collect();
+    sorted.sort();
+
+    let mut summarized: Vec<N> = Vec::with_capacity(sorted.len());
+    for (bits, prefix) in sorted {
+        // Sorted by address, so anything containing this block must be the last kept entry
+        if summarized
+            .last()
+            .is_some_and(|last| contains(last.to_block(), (bits, prefix)))
+        {
+            continue;
+        }
+        summarized.push(N::from_block(bits, prefix));
+
+        while let [.., a, b] = summarized[..] {
+            match merge_siblings(&a, &b) {
+                Some(parent) => {
+                    summarized.truncate(summarized.len() - 2);
+                    summarized.push(parent);
+                }
+                None => break,
+            }
+        }
+    }
+    summarized
+}
+
+/// The minimal free blocks of `parent` in address order once every hole is removed.
+/// Holes are trusted to be valid, callers check containment and overlaps if they care.
+pub(crate) fn carve<N: Cidr>(parent: &N, holes: &[N]) -> Vec<N> {
+    let holes: Vec<Block<N::Bits>> = holes.iter().map(normalized).collect();
+    let mut free = Vec::new();
+    carve_block(normalized(parent), &holes, &mut free);
+    free.into_iter()
+        .map(|(bits, prefix)| N::from_block(bits, prefix))
+        .collect()
+}
+
+/// Keeps `block` whole when no hole touches it, otherwise recurses into its halves.
+fn carve_block<W: AddressBits>(block: Block<W>, holes: &[Block<W>], free: &mut Vec<Block<W>>) {
+    if !holes.iter().any(|hole| overlaps(block, *hole)) {
+        free.push(block);
+        return;
+    }
+    if holes.iter().any(|hole| contains(*hole, block)) {
+        return;
+    }
+    // A hole lies strictly inside, so the block is wider than a single address and can be halved
+    let half = block.1 + 1;
+    carve_block((block.0, half), holes, free);
+    carve_block((block.0 | W::block_size(half), half), holes, free);
+}
+
+/// Backs `absorbed_blocks` for both families, validating every prefix before any shift.
+pub(crate) fn absorbed<N: Cidr>(aggregate: &N, networks: &[N]) -> Result<Vec<N>, NetworkError> {
+    aggregate.validate()?;
+    for network in networks {
+        network.validate()?;
+    }
+    let outer = normalized(aggregate);
+    let covered = summarize(networks);
+    if covered
+        .iter()
+        .any(|block| contains(block.to_block(), outer))
+    {
+        return Ok(Vec::new());
+    }
+    let inside: Vec<N> = covered
+        .into_iter()
+        .filter(|block| contains(outer, block.to_block()))
+        .collect();
+    Ok(carve(aggregate, &inside))
+}
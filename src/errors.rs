@@ -1,21 +1,97 @@
 use std::error::Error;
+use std::hash::{Hash, Hasher};
+use std::mem::discriminant;
+use std::net::AddrParseError;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub enum NetworkError {
     InvalidIpFormat,
+    /// The address parser's own explanation, equal to `InvalidIpFormat` whatever the text
+    AddrParse(String),
+    InvalidOctet(String),
+    LeadingZeroOctet(String),
     InvalidMaskFormat,
+    NonContiguousMask,
     EmptyNetworkList,
     InvalidHostsOrSubnets,
     InsufficientBits,
+    InvalidPrefixLength,
+    InvalidIpv6PrefixLength,
+    /// An IPv6 address where only IPv4 is supported
+    UnsupportedIpv6,
+    InconsistentBroadcast,
+    NoUsableHosts,
+    BelowMinimumPrefix,
+    MisalignedPrefix,
+    NotContiguous,
+    NotContained,
+    OverlappingNetworks,
+    TooManyAddresses,
+    NoFreeBlock,
+    Lossy,
 }
 
 impl Error for NetworkError {}
 
+impl NetworkError {
+    /// `AddrParse` only adds a reason to `InvalidIpFormat`, so both compare as the latter.
+    fn canonical(&self) -> &Self {
+        match self {
+            NetworkError::AddrParse(_) => &NetworkError::InvalidIpFormat,
+            error => error,
+        }
+    }
+}
+
+impl PartialEq for NetworkError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.canonical(), other.canonical()) {
+            (NetworkError::InvalidOctet(a), NetworkError::InvalidOctet(b))
+            | (NetworkError::LeadingZeroOctet(a), NetworkError::LeadingZeroOctet(b)) => a == b,
+            (a, b) => discriminant(a) == discriminant(b),
+        }
+    }
+}
+
+impl Eq for NetworkError {}
+
+impl Hash for NetworkError {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let error = self.canonical();
+        discriminant(error).hash(state);
+        if let NetworkError::InvalidOctet(octet) | NetworkError::LeadingZeroOctet(octet) = error {
+            octet.hash(state);
+        }
+    }
+}
+
+impl From<AddrParseError> for NetworkError {
+    fn from(error: AddrParseError) -> Self {
+        NetworkError::AddrParse(error.to_string())
+    }
+}
+
 impl std::fmt::Display for NetworkError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             NetworkError::InvalidIpFormat => write!(f, "Invalid IP address format."),
+            NetworkError::AddrParse(reason) => write!(f, "Invalid IP address format, {}.", reason),
+            NetworkError::InvalidOctet(octet) => {
+                write!(
+                    f,
+                    "Invalid octet '{}', octets must be between 0 and 255.",
+                    octet
+                )
+            }
+            NetworkError::LeadingZeroOctet(octet) => write!(
+                f,
+                "Invalid octet '{}', leading zeros are ambiguous with octal notation.",
+                octet
+            ),
             NetworkError::InvalidMaskFormat => write!(f, "Invalid subnet mask format."),
+            NetworkError::NonContiguousMask => {
+                write!(f, "Invalid subnet mask, the mask bits must be contiguous.")
+            }
             NetworkError::EmptyNetworkList => write!(f, "The network list is empty."),
             NetworkError::InvalidHostsOrSubnets => {
                 write!(f, "Invalid number of hosts or subnets provided.")
@@ -26,6 +102,50 @@ impl std::fmt::Display for NetworkError {
                     "Insufficient bits available for the required subnets or hosts."
                 )
             }
+            NetworkError::InvalidPrefixLength => {
+                write!(f, "Prefix length must be between 0 and 32.")
+            }
+            NetworkError::InvalidIpv6PrefixLength => {
+                write!(f, "IPv6 prefix length must be between 0 and 128.")
+            }
+            NetworkError::UnsupportedIpv6 => {
+                write!(f, "IPv6 networks are not supported here, only IPv4.")
+            }
+            NetworkError::InconsistentBroadcast => {
+                write!(
+                    f,
+                    "The broadcast address does not match the network address."
+                )
+            }
+            NetworkError::NoUsableHosts => write!(f, "The network has no usable hosts."),
+            NetworkError::BelowMinimumPrefix => {
+                write!(
+                    f,
+                    "The aggregate is shorter than the minimum allowed prefix."
+                )
+            }
+            NetworkError::MisalignedPrefix => {
+                write!(f, "The prefix has bits set beyond its prefix length.")
+            }
+            NetworkError::NotContiguous => {
+                write!(f, "The networks do not form a single contiguous block.")
+            }
+            NetworkError::NotContained => {
+                write!(f, "A network is not contained in its parent network.")
+            }
+            NetworkError::OverlappingNetworks => write!(f, "The networks overlap each other."),
+            NetworkError::TooManyAddresses => {
+                write!(f, "The network has too many addresses to expand.")
+            }
+            NetworkError::NoFreeBlock => {
+                write!(f, "No free block of the requested size is left.")
+            }
+            NetworkError::Lossy => {
+                write!(
+                    f,
+                    "The aggregate would cover addresses outside the networks."
+                )
+            }
         }
     }
 }
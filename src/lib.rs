@@ -1,7 +1,19 @@
 pub mod subnet;
 
+mod blocks;
+
 pub mod errors;
 
+pub mod plan;
+
+pub mod prelude;
+
+pub mod tagged;
+
+pub mod utils;
+
+pub mod vlsm;
+
 pub type Error = Box<dyn std::error::Error>;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,90 @@
+use crate::errors::NetworkError;
+use crate::subnet::Network;
+use log::info;
+use serde::Serialize;
+use std::net::Ipv4Addr;
+
+/// The subnet mask that fits the requested number of subnets and hosts into a base network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SubnetPlan {
+    pub prefix: u32,
+    pub netmask: Ipv4Addr,
+    /// Usable hosts in each subnet of the new prefix
+    pub usable_hosts: u32,
+    /// How many subnets of the new prefix fit into the base network
+    pub subnets: u64,
+}
+
+/// Named parameters for `SubnetPlan`, so subnets and hosts can't be swapped by accident.
+#[derive(Debug, Clone, Default)]
+pub struct SubnetPlanBuilder {
+    base_prefix: Option<u32>,
+    subnets: Option<u32>,
+    hosts: Option<u32>,
+}
+
+impl SubnetPlan {
+    pub fn builder() -> SubnetPlanBuilder {
+        SubnetPlanBuilder::default()
+    }
+}
+
+impl SubnetPlanBuilder {
+    pub fn base_prefix(mut self, prefix: u32) -> Self {
+        self.base_prefix = Some(prefix);
+        self
+    }
+
+    pub fn subnets(mut self, subnets: u32) -> Self {
+        self.subnets = Some(subnets);
+        self
+    }
+
+    pub fn hosts(mut self, hosts: u32) -> Self {
+        self.hosts = Some(hosts);
+        self
+    }
+
+    /// Fails with `InsufficientBits` unless the subnet and host bits together fit in the bits
+    /// left after the base prefix, e.g. 90 subnets of 350 hosts need a /16 or shorter.
+    pub fn solve(self) -> Result<SubnetPlan, NetworkError> {
+        let base_prefix = self
+            .base_prefix
+            .filter(|&prefix| prefix <= 32)
+            .ok_or(NetworkError::InvalidPrefixLength)?;
+        let (required_subnets, required_hosts) = match (self.subnets, self.hosts) {
+            (Some(subnets), Some(hosts)) if subnets > 0 && hosts > 0 => (subnets, hosts),
+            _ => return Err(NetworkError::InvalidHostsOrSubnets),
+        };
+
+        let host_bits = required_hosts
+            .checked_add(2)
+            .and_then(u32::checked_next_power_of_two)
+            .ok_or(NetworkError::InsufficientBits)?
+            .trailing_zeros();
+        let subnet_bits = required_subnets
+            .checked_next_power_of_two()
+            .ok_or(NetworkError::InsufficientBits)?
+            .trailing_zeros();
+
+        info!(
+            "Required hosts: {}, Required subnets: {}, Host bits: {}, Subnet bits: {}",
+            required_hosts, required_subnets, host_bits, subnet_bits,
+        );
+
+        if subnet_bits + host_bits > 32 - base_prefix {
+            return Err(NetworkError::InsufficientBits);
+        }
+
+        let prefix = base_prefix + subnet_bits;
+        info!("New mask prefix: {}", prefix);
+
+        let subnet = Network::new(Ipv4Addr::UNSPECIFIED, prefix);
+        Ok(SubnetPlan {
+            prefix,
+            netmask: subnet.netmask_address(),
+            usable_hosts: subnet.available_hosts(),
+            subnets: 1u64 << subnet_bits,
+        })
+    }
+}
@@ -0,0 +1,12 @@
+//! The commonly used types in one place, so `use subnetcalc::prelude::*;` is enough.
+//!
+//! Helpers such as `aggregate_networks`, `determine_subnet_mask`, `mask_to_u32` and
+//! `default_mask` are associated functions on `Network` and come along with it.
+
+pub use crate::errors::NetworkError;
+pub use crate::plan::{SubnetPlan, SubnetPlanBuilder};
+pub use crate::subnet::{
+    HostCountPolicy, IpNetwork, IpNetworkInfo, Ipv6Network, Ipv6NetworkInfo, Network, NetworkInfo,
+};
+pub use crate::tagged::{TaggedAggregate, TaggedNetwork};
+pub use crate::vlsm::VlsmAllocator;
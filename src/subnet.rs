@@ -1,42 +1,652 @@
-use crate::errors::NetworkError;
+use crate::blocks;
+/// Re-exported so `subnet::NetworkError` and `errors::NetworkError` name the same type.
+pub use crate::errors::NetworkError;
+use crate::plan::SubnetPlan;
+use crate::utils;
+use crate::vlsm::VlsmAllocator;
 use log::info;
-use std::net::Ipv4Addr;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+use std::net::{AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Equality and hashing compare the stored `ip` exactly, so `192.168.1.1/24` and
+/// `192.168.1.0/24` are distinct keys even though they describe the same network.
+/// Ordering is by address, then prefix length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Network {
     pub ip: Ipv4Addr,
     pub prefix: u32, // CIDR notation (e.g., /24)
 }
 
+/// How usable hosts are counted, environments disagree about /31 and /32.
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum HostCountPolicy {
+    /// Network and broadcast addresses are never usable, a /31 point-to-point link has neither
+    #[default]
+    Classic,
+    /// Classic counting, except a /32 is a single host
+    Rfc3021,
+    /// Every address in the range is usable
+    AllAddresses,
+}
+
+/// Whether an address bit belongs to the network or the host part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitKind {
+    Network,
+    Host,
+}
+
+/// Serializable snapshot of a network's derived addresses, used for machine-readable output.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NetworkInfo {
+    pub network: Ipv4Addr,
+    pub prefix: u32,
+    pub netmask: Ipv4Addr,
+    pub wildcard: Ipv4Addr,
+    /// `None` for a /31 point-to-point link, where both addresses are hosts
+    pub broadcast: Option<Ipv4Addr>,
+    /// `None` for a /32, which has no usable host
+    pub first_host: Option<Ipv4Addr>,
+    pub last_host: Option<Ipv4Addr>,
+    pub available_hosts: u64,
+    pub class: char,
+    pub scope: &'static str,
+}
+
+impl NetworkInfo {
+    pub fn new(network: &Network, policy: HostCountPolicy) -> Self {
+        let usable = network.usable_range().ok();
+        Self {
+            network: network.network_address(),
+            prefix: network.prefix,
+            netmask: network.netmask_address(),
+            wildcard: network.wildcard_address(),
+            broadcast: (!network.is_point_to_point()).then(|| network.broadcast_address()),
+            first_host: usable.map(|(first, _)| first),
+            last_host: usable.map(|(_, last)| last),
+            available_hosts: network.usable_hosts_with(policy),
+            class: network.ip_class(),
+            scope: network.scope(),
+        }
+    }
+}
+
+/// Serializable snapshot of an IPv6 network, which has no broadcast or wildcard address.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Ipv6NetworkInfo {
+    pub network: Ipv6Addr,
+    pub prefix: u32,
+    pub netmask: Ipv6Addr,
+    pub last_address: Ipv6Addr,
+    pub available_hosts: u128,
+}
+
+/// Either family's snapshot, serialized as the inner record without a tag.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum IpNetworkInfo {
+    V4(NetworkInfo),
+    V6(Ipv6NetworkInfo),
+}
+
+impl From<&Network> for NetworkInfo {
+    fn from(network: &Network) -> Self {
+        network.describe()
+    }
+}
+
 impl std::fmt::Display for Network {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}/{}", self.ip, self.prefix)
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Ipv6Network {
+    pub ip: Ipv6Addr,
+    pub prefix: u32, // CIDR notation (e.g., /64)
+}
+
+/// Either address family, for lists that mix IPv4 and IPv6 networks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IpNetwork {
+    V4(Network),
+    V6(Ipv6Network),
+}
+
+impl Ipv6Network {
+    pub fn new(ip: Ipv6Addr, prefix: u32) -> Self {
+        Self { ip, prefix }
+    }
+
+    pub fn validate(&self) -> Result<(), NetworkError> {
+        if self.prefix > 128 {
+            return Err(NetworkError::InvalidIpv6PrefixLength);
+        }
+        Ok(())
+    }
+
+    pub fn network_address(&self) -> Ipv6Addr {
+        Ipv6Addr::from(u128::from(self.ip) & Self::mask_to_u128(self.prefix))
+    }
+
+    pub fn netmask_address(&self) -> Ipv6Addr {
+        Ipv6Addr::from(Self::mask_to_u128(self.prefix))
+    }
+
+    /// The top of the range, the counterpart of an IPv4 broadcast address.
+    pub fn last_address(&self) -> Ipv6Addr {
+        Ipv6Addr::from(u128::from(self.ip) | !Self::mask_to_u128(self.prefix))
+    }
+
+    pub fn contains(&self, ip: Ipv6Addr) -> bool {
+        let mask = Self::mask_to_u128(self.prefix);
+        u128::from(ip) & mask == u128::from(self.ip) & mask
+    }
+
+    pub fn contains_network(&self, other: &Ipv6Network) -> bool {
+        self.prefix <= other.prefix && self.contains(other.ip)
+    }
+
+    /// Number of addresses, saturating at `u128::MAX` for `::/0`, which holds one more.
+    pub fn address_count(&self) -> u128 {
+        1u128.checked_shl(128 - self.prefix).unwrap_or(u128::MAX)
+    }
+
+    /// IPv6 reserves no network or broadcast address, so every address is a usable host.
+    pub fn available_hosts(&self) -> u128 {
+        self.address_count()
+    }
+
+    pub fn describe(&self) -> Ipv6NetworkInfo {
+        Ipv6NetworkInfo {
+            network: self.network_address(),
+            prefix: self.prefix,
+            netmask: self.netmask_address(),
+            last_address: self.last_address(),
+            available_hosts: self.available_hosts(),
+        }
+    }
+
+    /// The IPv6 counterpart of `Network::aggregate_networks`, the longest prefix covering
+    /// every input.
+    pub fn aggregate_networks(networks: &[Ipv6Network]) -> Result<Ipv6Network, NetworkError> {
+        if networks.is_empty() {
+            return Err(NetworkError::EmptyNetworkList);
+        }
+        let mut networks = networks
+            .iter()
+            .map(|net| {
+                net.validate()
+                    .map(|()| Ipv6Network::new(net.network_address(), net.prefix))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Normalizing can turn distinct inputs into copies, which would agree on all 128 bits
+        networks.sort();
+        networks.dedup();
+        if let [network] = networks[..] {
+            return Ok(network);
+        }
+
+        let first_address = u128::from(networks[0].ip);
+        let common_bits = networks
+            .iter()
+            .map(|net| (first_address ^ u128::from(net.ip)).leading_zeros())
+            .min()
+            .unwrap_or(128);
+        let aggregated = Ipv6Network::new(
+            Ipv6Addr::from(first_address & Self::mask_to_u128(common_bits)),
+            common_bits,
+        );
+        info!("Aggregated network: {}", aggregated);
+
+        Ok(aggregated)
+    }
+
+    /// The IPv6 counterpart of `Network::aggregate_exact`.
+    pub fn aggregate_exact(networks: &[Ipv6Network]) -> Result<Vec<Ipv6Network>, NetworkError> {
+        if networks.is_empty() {
+            return Err(NetworkError::EmptyNetworkList);
+        }
+        for network in networks {
+            network.validate()?;
+        }
+        Ok(Self::summarize_networks(networks))
+    }
+
+    /// The IPv6 counterpart of `Network::summarize_networks`.
+    pub fn summarize_networks(networks: &[Ipv6Network]) -> Vec<Ipv6Network> {
+        blocks::summarize(networks)
+    }
+
+    /// The IPv6 counterpart of `Network::absorbed_blocks`.
+    pub fn absorbed_blocks(
+        aggregate: &Ipv6Network,
+        networks: &[Ipv6Network],
+    ) -> Result<Vec<Ipv6Network>, NetworkError> {
+        blocks::absorbed(aggregate, networks)
+    }
+
+    pub fn overlaps(&self, other: &Ipv6Network) -> bool {
+        self.contains_network(other) || other.contains_network(self)
+    }
+
+    pub fn mask_to_u128(prefix: u32) -> u128 {
+        u128::MAX.checked_shl(128 - prefix).unwrap_or(0)
+    }
+}
+
+impl std::fmt::Display for Ipv6Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.ip, self.prefix)
+    }
+}
+
+/// Parses `addr/prefix`, a bare address is a single host /128.
+impl std::str::FromStr for Ipv6Network {
+    type Err = NetworkError;
+
+    fn from_str(network: &str) -> Result<Self, Self::Err> {
+        let network = network.trim_ascii();
+        let (ip_str, prefix_str) = network.split_once('/').unwrap_or((network, "128"));
+        let ip = ip_str.trim_ascii_end().parse::<Ipv6Addr>()?;
+        let prefix = prefix_str
+            .trim_ascii_start()
+            .parse::<u32>()
+            .map_err(|_| NetworkError::InvalidMaskFormat)?;
+        let network = Ipv6Network::new(ip, prefix);
+        network.validate()?;
+        Ok(network)
+    }
+}
+
+impl IpNetwork {
+    pub fn prefix(&self) -> u32 {
+        match self {
+            IpNetwork::V4(network) => network.prefix,
+            IpNetwork::V6(network) => network.prefix,
+        }
+    }
+
+    pub fn network_address(&self) -> IpAddr {
+        match self {
+            IpNetwork::V4(network) => IpAddr::V4(network.network_address()),
+            IpNetwork::V6(network) => IpAddr::V6(network.network_address()),
+        }
+    }
+
+    /// `None` for IPv6, which has no broadcast address.
+    pub fn broadcast_address(&self) -> Option<IpAddr> {
+        match self {
+            IpNetwork::V4(network) => Some(IpAddr::V4(network.broadcast_address())),
+            IpNetwork::V6(_) => None,
+        }
+    }
+
+    /// The top of the range, the broadcast address for IPv4.
+    pub fn last_address(&self) -> IpAddr {
+        match self {
+            IpNetwork::V4(network) => IpAddr::V4(network.broadcast_address()),
+            IpNetwork::V6(network) => IpAddr::V6(network.last_address()),
+        }
+    }
+
+    /// Number of addresses, saturating at `u128::MAX` for `::/0` like `Ipv6Network`.
+    pub fn address_count(&self) -> u128 {
+        match self {
+            IpNetwork::V4(network) => network.address_count().into(),
+            IpNetwork::V6(network) => network.address_count(),
+        }
+    }
+
+    pub fn describe(&self) -> IpNetworkInfo {
+        match self {
+            IpNetwork::V4(network) => IpNetworkInfo::V4(network.describe()),
+            IpNetwork::V6(network) => IpNetworkInfo::V6(network.describe()),
+        }
+    }
+
+    pub fn is_ipv4(&self) -> bool {
+        matches!(self, IpNetwork::V4(_))
+    }
+
+    /// Separates a mixed list by family, keeping the order within each.
+    pub fn split_families(networks: &[IpNetwork]) -> (Vec<Network>, Vec<Ipv6Network>) {
+        let mut v4 = Vec::new();
+        let mut v6 = Vec::new();
+        for network in networks {
+            match network {
+                IpNetwork::V4(network) => v4.push(*network),
+                IpNetwork::V6(network) => v6.push(*network),
+            }
+        }
+        (v4, v6)
+    }
+
+    /// Aggregates each family separately, one result per family present, IPv4 first.
+    pub fn aggregate_by_family(networks: &[IpNetwork]) -> Result<Vec<IpNetwork>, NetworkError> {
+        if networks.is_empty() {
+            return Err(NetworkError::EmptyNetworkList);
+        }
+        let (v4, v6) = Self::split_families(networks);
+        let mut aggregated = Vec::with_capacity(2);
+        if !v4.is_empty() {
+            aggregated.push(IpNetwork::V4(Network::aggregate_networks(&v4)?));
+        }
+        if !v6.is_empty() {
+            aggregated.push(IpNetwork::V6(Ipv6Network::aggregate_networks(&v6)?));
+        }
+        Ok(aggregated)
+    }
+
+    /// Like `aggregate_by_family`, but with `aggregate_exact`, IPv4 blocks first.
+    pub fn aggregate_exact_by_family(
+        networks: &[IpNetwork],
+    ) -> Result<Vec<IpNetwork>, NetworkError> {
+        if networks.is_empty() {
+            return Err(NetworkError::EmptyNetworkList);
+        }
+        let (v4, v6) = Self::split_families(networks);
+        let mut aggregated = Vec::new();
+        if !v4.is_empty() {
+            aggregated.extend(
+                Network::aggregate_exact(&v4)?
+                    .into_iter()
+                    .map(IpNetwork::V4),
+            );
+        }
+        if !v6.is_empty() {
+            aggregated.extend(
+                Ipv6Network::aggregate_exact(&v6)?
+                    .into_iter()
+                    .map(IpNetwork::V6),
+            );
+        }
+        Ok(aggregated)
+    }
+
+    /// Addresses of the other family are never contained.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self, ip) {
+            (IpNetwork::V4(network), IpAddr::V4(ip)) => network.contains(ip),
+            (IpNetwork::V6(network), IpAddr::V6(ip)) => network.contains(ip),
+            _ => false,
+        }
+    }
+
+    /// `absorbed_blocks` of the aggregate's own family, inputs of the other family are ignored.
+    pub fn absorbed_blocks(
+        aggregate: &IpNetwork,
+        networks: &[IpNetwork],
+    ) -> Result<Vec<IpNetwork>, NetworkError> {
+        let (v4, v6) = Self::split_families(networks);
+        Ok(match aggregate {
+            IpNetwork::V4(aggregate) => Network::absorbed_blocks(aggregate, &v4)?
+                .into_iter()
+                .map(IpNetwork::V4)
+                .collect(),
+            IpNetwork::V6(aggregate) => Ipv6Network::absorbed_blocks(aggregate, &v6)?
+                .into_iter()
+                .map(IpNetwork::V6)
+                .collect(),
+        })
+    }
+
+    /// Like `contains`, networks of the other family are never contained.
+    pub fn contains_network(&self, other: &IpNetwork) -> bool {
+        match (self, other) {
+            (IpNetwork::V4(network), IpNetwork::V4(other)) => network.contains_network(other),
+            (IpNetwork::V6(network), IpNetwork::V6(other)) => network.contains_network(other),
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IpNetwork::V4(network) => network.fmt(f),
+            IpNetwork::V6(network) => network.fmt(f),
+        }
+    }
+}
+
+/// Picks the family from the address, anything with a `:` is IPv6.
+impl std::str::FromStr for IpNetwork {
+    type Err = NetworkError;
+
+    fn from_str(network: &str) -> Result<Self, Self::Err> {
+        if network.contains(':') {
+            Ipv6Network::from_str(network).map(IpNetwork::V6)
+        } else {
+            Network::from_str(network).map(IpNetwork::V4)
+        }
+    }
+}
+
+/// Parses `a.b.c.d/prefix`, `a.b.c.d/netmask` or a bare `a.b.c.d` with the classful default mask.
+/// Octets with leading zeros such as `010` are rejected outright, since some tools read them as octal.
+/// The address may also be written as eight hex digits, e.g. `0xC0A80100/24`.
 impl std::str::FromStr for Network {
     type Err = NetworkError;
 
     fn from_str(subnet: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_default(subnet, None)
+    }
+}
+
+impl Network {
+    /// Parses like `FromStr`, but a maskless input gets `default_prefix` when one is given
+    /// instead of the classful default mask.
+    pub fn parse_with_default(
+        subnet: &str,
+        default_prefix: Option<u32>,
+    ) -> Result<Network, NetworkError> {
+        // Tolerate padding pasted from spreadsheets, e.g. " 192.168.1.0 / 24\t"
+        let subnet = subnet.trim_ascii();
         let (ip_str, mask_str) = subnet.split_once('/').unwrap_or((subnet, ""));
-        let ip = Ipv4Addr::from_str(ip_str).map_err(|_| NetworkError::InvalidIpFormat)?;
-        let mask = if mask_str.is_empty() {
-            Self::default_mask(ip)
-        } else {
-            mask_str
-                .parse::<u32>()
-                .map_err(|_| NetworkError::InvalidMaskFormat)?
+        let (ip_str, mask_str) = (ip_str.trim_ascii_end(), mask_str.trim_ascii_start());
+        if let Some(octet) = ip_str.split('.').find(|octet| {
+            octet.len() > 1 && octet.starts_with('0') && octet.bytes().all(|b| b.is_ascii_digit())
+        }) {
+            return Err(NetworkError::LeadingZeroOctet(octet.to_string()));
+        }
+        let ip = match ip_str.strip_prefix("0x").or(ip_str.strip_prefix("0X")) {
+            Some(hex) => Self::parse_hex_ip(hex)?,
+            None => ip_str
+                .parse::<Ipv4Addr>()
+                .map_err(|e| Self::diagnose_ip(ip_str, e))?,
+        };
+        let mask = match (mask_str.is_empty(), default_prefix) {
+            (true, Some(prefix)) if prefix <= 32 => prefix,
+            (true, Some(_)) => return Err(NetworkError::InvalidPrefixLength),
+            (true, None) => Self::default_mask(ip),
+            (false, _) => Self::parse_mask(mask_str)?,
         };
         info!("Parsed network: IP = {}, Mask = {}", ip, mask);
         Ok(Network::new(ip, mask))
     }
-}
 
-impl Network {
+    /// Parses every input with `FromStr`, failing with the index of the first bad entry.
+    pub fn parse_list(inputs: &[impl AsRef<str>]) -> Result<Vec<Network>, (usize, NetworkError)> {
+        Self::parse_list_with_default(inputs, None)
+    }
+
+    /// Like `parse_list`, with `default_prefix` applied as in `parse_with_default`.
+    pub fn parse_list_with_default(
+        inputs: &[impl AsRef<str>],
+        default_prefix: Option<u32>,
+    ) -> Result<Vec<Network>, (usize, NetworkError)> {
+        inputs
+            .iter()
+            .enumerate()
+            .map(|(index, input)| {
+                Self::parse_with_default(input.as_ref(), default_prefix).map_err(|e| (index, e))
+            })
+            .collect()
+    }
+
+    /// Like `FromStr`, but also accepts the abbreviated dotted form some legacy tools emit,
+    /// padding the missing octets with zeros, e.g. `10/8` or `172.16/12`. The shorthand needs
+    /// an explicit mask, a bare `10` stays an error.
+    pub fn parse_tolerant(subnet: &str) -> Result<Network, NetworkError> {
+        let subnet = subnet.trim_ascii();
+        if let Some((ip_str, mask_str)) = subnet.split_once('/') {
+            let ip_str = ip_str.trim_ascii_end();
+            let octets = ip_str.split('.').count();
+            let abbreviated = octets < 4
+                && ip_str
+                    .split('.')
+                    .all(|octet| !octet.is_empty() && octet.bytes().all(|b| b.is_ascii_digit()));
+            if abbreviated {
+                let padded = format!("{}{}/{}", ip_str, ".0".repeat(4 - octets), mask_str);
+                return Self::parse_with_default(&padded, None);
+            }
+        }
+        Self::parse_with_default(subnet, None)
+    }
+
+    /// Shortest prefix `expand` accepts, anything larger could exhaust memory.
+    pub const EXPAND_MIN_PREFIX: u32 = 16;
+
+    /// Shortest parent prefix `coverage_bitmap` accepts, one entry per address adds up quickly.
+    pub const BITMAP_MIN_PREFIX: u32 = 16;
+
+    /// Most subnets `split` returns, the same bound `expand` puts on addresses.
+    pub const SPLIT_MAX_SUBNETS: u32 = 1 << 16;
+
     pub fn new(ip: Ipv4Addr, mask: u32) -> Self {
         Self { ip, prefix: mask }
     }
 
+    /// Like `new`, but validates the prefix and stores the network address with the host bits cleared.
+    pub fn new_normalized(ip: Ipv4Addr, prefix: u32) -> Result<Self, NetworkError> {
+        let network = Self::new(ip, prefix);
+        network.validate()?;
+        Ok(Self::new(network.network_address(), prefix))
+    }
+
+    /// Checks the invariants `new` does not enforce, i.e. that the prefix is within 0..=32.
+    pub fn validate(&self) -> Result<(), NetworkError> {
+        if self.prefix > 32 {
+            return Err(NetworkError::InvalidPrefixLength);
+        }
+        Ok(())
+    }
+
+    /// True when the prefix is within 0..=32 and `ip` has no host bits set, e.g. for imported data.
+    pub fn is_well_formed(&self) -> bool {
+        self.validate().is_ok() && self.is_aligned()
+    }
+
+    pub fn checked_network(&self) -> Option<Ipv4Addr> {
+        let mask = Self::checked_mask(self.prefix)?;
+        Some(Ipv4Addr::from(u32::from(self.ip) & mask))
+    }
+
+    pub fn checked_broadcast(&self) -> Option<Ipv4Addr> {
+        let mask = Self::checked_mask(self.prefix)?;
+        Some(Ipv4Addr::from(u32::from(self.ip) | !mask))
+    }
+
+    /// Usable hosts without panicking, `None` only when the prefix is out of range.
+    pub fn checked_hosts(&self) -> Option<u32> {
+        let host_bits = 32u32.checked_sub(self.prefix)?;
+        if host_bits == 1 {
+            return Some(2);
+        }
+        let addresses = 1u64 << host_bits;
+        u32::try_from(addresses.saturating_sub(2)).ok()
+    }
+
+    /// Recovers the CIDR from a network/broadcast pair, e.g. from a firewall export.
+    pub fn from_network_broadcast(
+        network: Ipv4Addr,
+        broadcast: Ipv4Addr,
+    ) -> Result<Network, NetworkError> {
+        let network_u32 = u32::from(network);
+        let host_bits = network_u32 ^ u32::from(broadcast);
+        // The differing bits must be a contiguous run of low bits, all clear in the network
+        if host_bits.leading_zeros() + host_bits.count_ones() != 32 || network_u32 & host_bits != 0
+        {
+            return Err(NetworkError::InconsistentBroadcast);
+        }
+        Ok(Network::new(network, host_bits.leading_zeros()))
+    }
+
+    /// The network address and prefix as two integers, e.g. for database columns.
+    pub fn to_u32_pair(&self) -> (u32, u32) {
+        (u32::from(self.network_address()), self.prefix)
+    }
+
+    /// Inverse of `to_u32_pair`, rejecting prefixes over 32 and networks with host bits set.
+    pub fn from_u32_pair(network: u32, prefix: u32) -> Result<Network, NetworkError> {
+        let network = Network::new(Ipv4Addr::from(network), prefix);
+        network.validate()?;
+        if !network.is_aligned() {
+            return Err(NetworkError::MisalignedPrefix);
+        }
+        Ok(network)
+    }
+
+    /// Kind of every address bit, most significant first, e.g. for colouring a binary view.
+    pub fn bit_kinds(&self) -> [BitKind; 32] {
+        std::array::from_fn(|i| {
+            if (i as u32) < self.prefix {
+                BitKind::Network
+            } else {
+                BitKind::Host
+            }
+        })
+    }
+
+    /// Start of the raw address span, the network address itself rather than its first host.
+    pub fn first_address(&self) -> Ipv4Addr {
+        self.network_address()
+    }
+
+    /// End of the raw address span, the broadcast address rather than the last host.
+    pub fn last_address(&self) -> Ipv4Addr {
+        self.broadcast_address()
+    }
+
+    /// Bit `pos` of the network address counting from the most significant, `None` past bit 31.
+    pub fn network_bit_at(&self, pos: u32) -> Option<bool> {
+        (pos < 32).then(|| u32::from(self.network_address()) & (1 << (31 - pos)) != 0)
+    }
+
+    /// True when bit `pos`, counting from the most significant, falls in the host part.
+    /// Positions past bit 31 are not bits of the address at all, so they are never host bits.
+    pub fn is_host_bit(&self, pos: u32) -> bool {
+        pos < 32 && pos >= self.prefix
+    }
+
+    /// The network address as its 32 binary digits, most significant bit first.
+    pub fn bitstring(&self) -> String {
+        format!("{:032b}", u32::from(self.network_address()))
+    }
+
+    pub fn from_bitstring(bits: &str, prefix: u32) -> Result<Network, NetworkError> {
+        if bits.len() != 32 || !bits.bytes().all(|b| b == b'0' || b == b'1') {
+            return Err(NetworkError::InvalidIpFormat);
+        }
+        let ip = u32::from_str_radix(bits, 2).map_err(|_| NetworkError::InvalidIpFormat)?;
+        let network = Network::new(Ipv4Addr::from(ip), prefix);
+        network.validate()?;
+        Ok(network)
+    }
+
+    pub fn network_address(&self) -> Ipv4Addr {
+        Ipv4Addr::from(u32::from(self.ip) & Self::mask_to_u32(self.prefix))
+    }
+
+    /// True when `ip` is the network address itself rather than a host inside it.
+    pub fn is_aligned(&self) -> bool {
+        self.network_address() == self.ip
+    }
+
     pub fn broadcast_address(&self) -> Ipv4Addr {
         let ip_u32 = u32::from(self.ip);
         let wildcard = !Self::mask_to_u32(self.prefix);
@@ -61,32 +671,715 @@ impl Network {
         }
     }
 
+    /// Describes addresses whose historical class is misleading, e.g. 127.0.0.0/8 is class A but loopback.
+    pub fn special_class_note(&self) -> Option<&'static str> {
+        match self.ip {
+            ip if ip.octets()[0] == 0 => Some("this-network"),
+            ip if ip.is_loopback() => Some("loopback"),
+            ip if ip.is_link_local() => Some("link-local"),
+            ip if ip.is_multicast() => Some("multicast"),
+            ip if ip.is_broadcast() => Some("limited-broadcast"),
+            ip if ip.octets()[0] >= 240 => Some("reserved"),
+            _ => None,
+        }
+    }
+
+    /// Where the network is reachable from, a `special_class_note`, `private` or `public`.
+    pub fn scope(&self) -> &'static str {
+        match self.special_class_note() {
+            Some(note) => note,
+            None if self.ip.is_private() => "private",
+            None => "public",
+        }
+    }
+
+    /// Every derived field at once, the backing record for the JSON, CSV and table output.
+    pub fn describe(&self) -> NetworkInfo {
+        NetworkInfo::new(self, HostCountPolicy::default())
+    }
+
+    /// True when the prefix is shorter than the classful default mask, so the network covers
+    /// several legacy class blocks, e.g. `10.0.0.0/7`.
+    pub fn spans_classful_boundary(&self) -> bool {
+        self.prefix < Self::default_mask(self.network_address())
+    }
+
+    /// True when the mask boundary falls inside an octet rather than between two, e.g. /27.
+    pub fn crosses_octet_boundary(&self) -> bool {
+        !self.prefix.is_multiple_of(8)
+    }
+
+    /// RFC 3021 /31 links have no network or broadcast address, both addresses are hosts.
+    pub fn is_point_to_point(&self) -> bool {
+        self.prefix == 31
+    }
+
     pub fn available_hosts(&self) -> u32 {
-        2u32.pow(32 - self.prefix) - 2
+        if self.is_point_to_point() {
+            return 2;
+        }
+        // At most 2^32 - 2, so the cast is lossless
+        self.address_count().saturating_sub(2) as u32
+    }
+
+    /// The same network narrowed to the longest prefix that still has `hosts` usable addresses.
+    pub fn shrink_to_fit(&self, hosts: u32) -> Result<Network, NetworkError> {
+        let prefix = Self::prefix_for_hosts(hosts)?;
+        if prefix < self.prefix {
+            return Err(NetworkError::InsufficientBits);
+        }
+        Ok(Network::new(self.network_address(), prefix))
+    }
+
+    /// Canonical one-line description, e.g. `192.168.1.64/26 (255.255.255.192, 62 hosts, class C)`.
+    pub fn summary_line(&self) -> String {
+        format!(
+            "{}/{} ({}, {} hosts, class {})",
+            self.network_address(),
+            self.prefix,
+            self.netmask_address(),
+            self.available_hosts(),
+            self.ip_class()
+        )
+    }
+
+    pub fn contains(&self, ip: Ipv4Addr) -> bool {
+        self.address_matcher()(ip)
+    }
+
+    pub fn contains_any<I: IntoIterator<Item = Ipv4Addr>>(&self, ips: I) -> bool {
+        ips.into_iter().any(self.address_matcher())
+    }
+
+    pub fn count_contained<I: IntoIterator<Item = Ipv4Addr>>(&self, ips: I) -> usize {
+        let matches = self.address_matcher();
+        ips.into_iter().filter(|&ip| matches(ip)).count()
+    }
+
+    /// Membership test with the mask and network computed once, for checking many addresses.
+    fn address_matcher(&self) -> impl Fn(Ipv4Addr) -> bool {
+        let mask = Self::mask_to_u32(self.prefix);
+        let network = u32::from(self.ip) & mask;
+        move |ip| u32::from(ip) & mask == network
+    }
+
+    /// Returns true when the whole range of `other` lies within this network.
+    pub fn contains_network(&self, other: &Network) -> bool {
+        let mask = Self::mask_to_u32(self.prefix);
+        other.prefix >= self.prefix && u32::from(other.ip) & mask == u32::from(self.ip) & mask
+    }
+
+    /// Returns true when this network lies strictly inside `other`, equal ranges excluded.
+    pub fn is_subnet_of(&self, other: &Network) -> bool {
+        self.prefix > other.prefix && other.contains_network(self)
+    }
+
+    /// Returns true when `other` lies strictly inside this network, equal ranges excluded.
+    pub fn is_supernet_of(&self, other: &Network) -> bool {
+        other.is_subnet_of(self)
+    }
+
+    /// Returns true when the two networks share at least one address.
+    pub fn overlaps(&self, other: &Network) -> bool {
+        self.contains_network(other) || other.contains_network(self)
+    }
+
+    /// Number of leading bits the two network addresses agree on, 0..=32.
+    pub fn common_prefix_len(&self, other: &Network) -> u32 {
+        (u32::from(self.network_address()) ^ u32::from(other.network_address())).leading_zeros()
+    }
+
+    pub fn address_count(&self) -> u64 {
+        1u64 << (32 - self.prefix)
+    }
+
+    /// How many times larger `a` is than `b` by address count.
+    pub fn size_ratio(a: &Network, b: &Network) -> f64 {
+        a.address_count() as f64 / b.address_count() as f64
+    }
+
+    /// Prefix bits `a` is shorter than `b` by, positive when `a` is the larger network.
+    pub fn prefix_delta(a: &Network, b: &Network) -> i32 {
+        b.prefix as i32 - a.prefix as i32
+    }
+
+    /// The IPv4-mapped IPv6 form, e.g. `192.168.1.0/24` -> `::ffff:192.168.1.0/120`.
+    pub fn to_ipv4_mapped_ipv6(&self) -> Ipv6Network {
+        Ipv6Network::new(self.ip.to_ipv6_mapped(), 96 + self.prefix)
+    }
+
+    /// First and last usable host, erroring for /32 which has no usable hosts.
+    pub fn usable_range(&self) -> Result<(Ipv4Addr, Ipv4Addr), NetworkError> {
+        let network = u32::from(self.network_address());
+        let broadcast = u32::from(self.broadcast_address());
+        match self.prefix {
+            32 => Err(NetworkError::NoUsableHosts),
+            31 => Ok((Ipv4Addr::from(network), Ipv4Addr::from(broadcast))),
+            _ => Ok((Ipv4Addr::from(network + 1), Ipv4Addr::from(broadcast - 1))),
+        }
+    }
+
+    /// Lazily yields every usable host, see `usable_range`.
+    pub fn hosts(&self) -> impl Iterator<Item = Ipv4Addr> {
+        let (first, last) = self
+            .usable_range()
+            .map_or((1, 0), |(first, last)| (u32::from(first), u32::from(last)));
+        (first..=last).map(Ipv4Addr::from)
+    }
+
+    /// The `n`-th usable host counting from zero, `None` past the last one, see `usable_range`.
+    pub fn nth_host(&self, n: u32) -> Option<Ipv4Addr> {
+        let (first, last) = self.usable_range().ok()?;
+        let host = u32::from(first).checked_add(n)?;
+        (host <= u32::from(last)).then(|| Ipv4Addr::from(host))
+    }
+
+    /// Lazily yields every address from the network through the broadcast address.
+    pub fn addresses(&self) -> impl Iterator<Item = Ipv4Addr> {
+        let first = u32::from(self.network_address());
+        let last = u32::from(self.broadcast_address());
+        (first..=last).map(Ipv4Addr::from)
+    }
+
+    /// Every address as a dotted string, refusing prefixes shorter than `EXPAND_MIN_PREFIX`.
+    pub fn expand(&self) -> Result<Vec<String>, NetworkError> {
+        if self.prefix < Self::EXPAND_MIN_PREFIX {
+            return Err(NetworkError::TooManyAddresses);
+        }
+        Ok(self.addresses().map(|ip| ip.to_string()).collect())
+    }
+
+    /// Every supernet from the immediate parent up to `0.0.0.0/0`.
+    pub fn ancestors(&self) -> impl Iterator<Item = Network> {
+        let ip = self.ip;
+        (0..self.prefix.min(32)).rev().map(move |prefix| {
+            Network::new(
+                Ipv4Addr::from(u32::from(ip) & Self::mask_to_u32(prefix)),
+                prefix,
+            )
+        })
+    }
+
+    /// Lazily yields the PTR name of every usable host.
+    pub fn host_arpa_names(&self) -> impl Iterator<Item = String> {
+        self.hosts().map(utils::ipv4_to_arpa)
+    }
+
+    pub fn usable_hosts_with(&self, policy: HostCountPolicy) -> u64 {
+        let addresses = self.address_count();
+        match (policy, self.prefix) {
+            (HostCountPolicy::AllAddresses, _) | (HostCountPolicy::Rfc3021, 32) => addresses,
+            _ => self.available_hosts().into(),
+        }
     }
 
     pub fn aggregate_networks(networks: &[Network]) -> Result<Network, NetworkError> {
         if networks.is_empty() {
             return Err(NetworkError::EmptyNetworkList);
         }
+        let mut networks = networks
+            .iter()
+            .map(|net| Network::new_normalized(net.ip, net.prefix))
+            .collect::<Result<Vec<_>, _>>()?;
+        // Normalizing can turn distinct inputs into copies, which would agree on all 32 bits
+        networks.sort();
+        networks.dedup();
         if networks.len() == 1 {
-            info!("Single network provided: {:?}", networks[0]);
+            info!("Single network provided: {}", networks[0].summary_line());
             return Ok(networks[0]);
         }
 
-        let common_prefix = Self::find_common_prefix(networks);
-        let common_bits = Self::count_common_bits(networks);
+        let common_prefix = Self::find_common_prefix(&networks);
+        let common_bits = Self::count_common_bits(&networks);
         info!("Common prefix length: {}", common_bits);
 
         let new_mask = Self::mask_to_u32(common_bits);
-        let aggregated_ip = Ipv4Addr::from(common_prefix & new_mask);
+        let aggregated = Network::new(Ipv4Addr::from(common_prefix & new_mask), common_bits);
+        info!("Aggregated network: {}", aggregated.summary_line());
+
+        Ok(aggregated)
+    }
+
+    /// Like `aggregate_networks`, but refuses results shorter than `/min_prefix`.
+    pub fn aggregate_networks_with_floor(
+        networks: &[Network],
+        min_prefix: u32,
+    ) -> Result<Network, NetworkError> {
+        let aggregated = Self::aggregate_networks(networks)?;
+        if aggregated.prefix < min_prefix {
+            return Err(NetworkError::BelowMinimumPrefix);
+        }
+        Ok(aggregated)
+    }
+
+    /// Merges only adjacent or contained networks, returning the minimal list of CIDRs that
+    /// covers exactly the input addresses, unlike `aggregate_networks` which may absorb gaps.
+    pub fn aggregate_exact(networks: &[Network]) -> Result<Vec<Network>, NetworkError> {
+        if networks.is_empty() {
+            return Err(NetworkError::EmptyNetworkList);
+        }
+        for network in networks {
+            network.validate()?;
+        }
+        Ok(Self::summarize_networks(networks))
+    }
+
+    /// Networks present only in `new` (added) and only in `old` (removed), in input order.
+    pub fn diff(old: &[Network], new: &[Network]) -> (Vec<Network>, Vec<Network>) {
+        let old_set: HashSet<&Network> = old.iter().collect();
+        let new_set: HashSet<&Network> = new.iter().collect();
+        let added = new
+            .iter()
+            .filter(|net| !old_set.contains(net))
+            .copied()
+            .collect();
+        let removed = old
+            .iter()
+            .filter(|net| !new_set.contains(net))
+            .copied()
+            .collect();
+        (added, removed)
+    }
+
+    /// Aggregates only when the sorted inputs tile a single aligned block exactly,
+    /// with no gaps, overlaps or wasted addresses.
+    pub fn aggregate_contiguous(networks: &[Network]) -> Result<Network, NetworkError> {
+        let mut ranges: Vec<(u32, u32)> = networks
+            .iter()
+            .map(|net| {
+                (
+                    u32::from(net.network_address()),
+                    u32::from(net.broadcast_address()),
+                )
+            })
+            .collect();
+        ranges.sort_unstable();
+
+        let (first, last) = match (ranges.first(), ranges.last()) {
+            (Some(first), Some(last)) => (first.0, last.1),
+            _ => return Err(NetworkError::EmptyNetworkList),
+        };
+        if ranges
+            .windows(2)
+            .any(|pair| pair[0].1.checked_add(1) != Some(pair[1].0))
+        {
+            return Err(NetworkError::NotContiguous);
+        }
+
+        Self::from_network_broadcast(Ipv4Addr::from(first), Ipv4Addr::from(last))
+            .map_err(|_| NetworkError::NotContiguous)
+    }
+
+    /// The blocks of `aggregate` that none of `networks` cover, i.e. what aggregating them
+    /// absorbed. Empty when the aggregate is coverage-equivalent to the inputs. Inputs outside
+    /// the aggregate cover nothing of it, and one containing it leaves nothing absorbed.
+    pub fn absorbed_blocks(
+        aggregate: &Network,
+        networks: &[Network],
+    ) -> Result<Vec<Network>, NetworkError> {
+        blocks::absorbed(aggregate, networks)
+    }
+
+    /// The common supernet, but only when the union of the inputs covers every one of its
+    /// addresses, failing with `Lossy` otherwise. Unlike `aggregate_contiguous`, overlapping
+    /// and duplicate inputs are fine.
+    pub fn aggregate_lossless(networks: &[Network]) -> Result<Network, NetworkError> {
+        let supernet = Self::common_supernet(networks)?;
+        match Self::summarize_networks(networks)[..] {
+            [single] if single == supernet => Ok(supernet),
+            _ => Err(NetworkError::Lossy),
+        }
+    }
+
+    /// Merges exactly two sibling networks into their parent, failing with `NotContiguous`
+    /// whenever the merge would cover addresses outside the pair.
+    pub fn try_aggregate_pair(a: &Network, b: &Network) -> Result<Network, NetworkError> {
+        let a = Network::new(a.network_address(), a.prefix);
+        let b = Network::new(b.network_address(), b.prefix);
+        let (low, high) = if u32::from(a.ip) <= u32::from(b.ip) {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        blocks::merge_siblings(&low, &high).ok_or(NetworkError::NotContiguous)
+    }
+
+    /// Smallest single network covering the full range of every input, unlike
+    /// `aggregate_networks` it never loses coverage.
+    pub fn common_supernet(networks: &[Network]) -> Result<Network, NetworkError> {
+        let first = networks
+            .iter()
+            .map(|net| u32::from(net.network_address()))
+            .min()
+            .ok_or(NetworkError::EmptyNetworkList)?;
+        let last = networks
+            .iter()
+            .map(|net| u32::from(net.broadcast_address()))
+            .max()
+            .ok_or(NetworkError::EmptyNetworkList)?;
+
+        let prefix = (first ^ last).leading_zeros();
+        let supernet = Ipv4Addr::from(first & Self::mask_to_u32(prefix));
+        info!("Common supernet: {}/{}", supernet, prefix);
+
+        Ok(Network::new(supernet, prefix))
+    }
+
+    /// Fraction of `parent` covered by `allocated`, which must be contained in it and disjoint.
+    pub fn utilization(parent: &Network, allocated: &[Network]) -> Result<f64, NetworkError> {
+        Self::validate_children(parent, allocated)?;
+        let used: u64 = allocated.iter().map(Network::address_count).sum();
+        Ok(used as f64 / parent.address_count() as f64)
+    }
+
+    /// One flag per address of `parent`, set where an `allocated` network covers it.
+    pub fn coverage_bitmap(
+        parent: &Network,
+        allocated: &[Network],
+    ) -> Result<Vec<bool>, NetworkError> {
+        if parent.prefix < Self::BITMAP_MIN_PREFIX {
+            return Err(NetworkError::TooManyAddresses);
+        }
+        Self::validate_children(parent, allocated)?;
+        let start = u32::from(parent.network_address());
+        let mut bitmap = vec![false; parent.address_count() as usize];
+        for network in allocated {
+            let offset = (u32::from(network.network_address()) - start) as usize;
+            bitmap[offset..offset + network.address_count() as usize].fill(true);
+        }
+        Ok(bitmap)
+    }
+
+    /// Summarizes the networks separately within each `/group_prefix` supernet, keyed by that
+    /// supernet so iteration is in ascending address order. Networks shorter than the group
+    /// prefix are keyed by themselves.
+    pub fn aggregate_grouped(
+        networks: &[Network],
+        group_prefix: u32,
+    ) -> Result<BTreeMap<Network, Vec<Network>>, NetworkError> {
+        let mut groups: BTreeMap<Network, Vec<Network>> = BTreeMap::new();
+        for network in networks {
+            let key = Network::new_normalized(network.ip, group_prefix.min(network.prefix))?;
+            groups.entry(key).or_default().push(*network);
+        }
+        for members in groups.values_mut() {
+            *members = Self::summarize_networks(members);
+        }
+        Ok(groups)
+    }
+
+    /// Every summarized network of `aggregate_grouped` in one list, sorted by address.
+    pub fn flatten_groups(groups: &BTreeMap<Network, Vec<Network>>) -> Vec<Network> {
+        let mut flattened: Vec<Network> = groups.values().flatten().copied().collect();
+        flattened.sort();
+        flattened
+    }
+
+    /// The free blocks left in `parent` once `hole` is removed, see `subtract_many`.
+    pub fn subtract(parent: &Network, hole: &Network) -> Result<Vec<Network>, NetworkError> {
+        Self::subtract_many(parent, std::slice::from_ref(hole))
+    }
+
+    /// Removes every hole from `parent` and returns the minimal free blocks in address order.
+    /// The holes must lie inside the parent and must not overlap each other.
+    pub fn subtract_many(
+        parent: &Network,
+        holes: &[Network],
+    ) -> Result<Vec<Network>, NetworkError> {
+        Self::validate_children(parent, holes)?;
+        Ok(blocks::carve(parent, holes))
+    }
+
+    /// Everything in `parent` except this network, which must lie inside it, see `subtract`.
+    pub fn complement_within(&self, parent: &Network) -> Result<Vec<Network>, NetworkError> {
+        Self::subtract(parent, self)
+    }
+
+    /// The lowest `/prefix` block of `parent` that overlaps none of the `used` networks,
+    /// used networks outside `parent` are ignored.
+    pub fn first_free(
+        parent: &Network,
+        used: &[Network],
+        prefix: u32,
+    ) -> Result<Network, NetworkError> {
+        if prefix > 32 || prefix < parent.prefix {
+            return Err(NetworkError::InvalidPrefixLength);
+        }
+        if used.iter().any(|network| network.contains_network(parent)) {
+            return Err(NetworkError::NoFreeBlock);
+        }
+        // With the covering case gone, anything overlapping the parent lies inside it
+        let inside: Vec<Network> = used
+            .iter()
+            .copied()
+            .filter(|network| network.overlaps(parent))
+            .collect();
+        let free = Self::subtract_many(parent, &Self::summarize_networks(&inside))?;
+        free.iter()
+            .find(|block| block.prefix <= prefix)
+            .map(|block| Network::new(block.ip, prefix))
+            .ok_or(NetworkError::NoFreeBlock)
+    }
+
+    fn validate_children(parent: &Network, children: &[Network]) -> Result<(), NetworkError> {
+        if !children.iter().all(|child| parent.contains_network(child)) {
+            return Err(NetworkError::NotContained);
+        }
+        let mut sorted = children.to_vec();
+        sorted.sort_by_key(|net| u32::from(net.network_address()));
+        if sorted.windows(2).any(|pair| pair[0].overlaps(&pair[1])) {
+            return Err(NetworkError::OverlappingNetworks);
+        }
+        Ok(())
+    }
+
+    /// Reduces the list to the minimal set of networks covering exactly the same addresses:
+    /// networks contained in others are dropped and sibling pairs are merged until stable.
+    pub fn summarize_networks(networks: &[Network]) -> Vec<Network> {
+        let summarized = blocks::summarize(networks);
+        info!(
+            "Summarized {} networks into {}",
+            networks.len(),
+            summarized.len()
+        );
+
+        summarized
+    }
+
+    /// Splits the network into the smallest power of two of equal subnets that is at least `count`,
+    /// refusing more than `SPLIT_MAX_SUBNETS`.
+    pub fn split(&self, count: u32) -> Result<Vec<Network>, NetworkError> {
+        if count == 0 {
+            return Err(NetworkError::InvalidHostsOrSubnets);
+        }
+        if count > Self::SPLIT_MAX_SUBNETS {
+            return Err(NetworkError::TooManyAddresses);
+        }
+        let subnet_bits = count
+            .checked_next_power_of_two()
+            .ok_or(NetworkError::InsufficientBits)?
+            .trailing_zeros();
+        if subnet_bits > 32 - self.prefix {
+            return Err(NetworkError::InsufficientBits);
+        }
+
+        let new_prefix = self.prefix + subnet_bits;
+        let network = u64::from(u32::from(self.network_address()));
+        let size = 1u64 << (32 - new_prefix);
         info!(
-            "Aggregated network: IP = {}, Mask = {}",
-            aggregated_ip,
-            Ipv4Addr::from(new_mask.to_be_bytes())
+            "Splitting {} into {} subnets of /{}",
+            self,
+            1u64 << subnet_bits,
+            new_prefix
         );
 
-        Ok(Network::new(aggregated_ip, common_bits))
+        Ok((0..1u64 << subnet_bits)
+            .map(|i| Network::new(Ipv4Addr::from((network + i * size) as u32), new_prefix))
+            .collect())
+    }
+
+    /// Carves the first subnet of `new_prefix` off the network and returns it together with
+    /// the remaining free blocks in ascending order, e.g. a /24 at /26 leaves `.64/26, .128/25`.
+    pub fn split_off_first(
+        &self,
+        new_prefix: u32,
+    ) -> Result<(Network, Vec<Network>), NetworkError> {
+        if new_prefix > 32 {
+            return Err(NetworkError::InvalidPrefixLength);
+        }
+        if new_prefix < self.prefix {
+            return Err(NetworkError::InsufficientBits);
+        }
+
+        let network = self.network_address();
+        // The upper half at every level between the two prefixes stays free
+        let remainder = (self.prefix + 1..=new_prefix)
+            .rev()
+            .map(|prefix| {
+                let upper = u32::from(network) | (1 << (32 - prefix));
+                Network::new(Ipv4Addr::from(upper), prefix)
+            })
+            .collect();
+
+        Ok((Network::new(network, new_prefix), remainder))
+    }
+
+    /// The smallest subnet at the start of this network holding at least `percent` percent of
+    /// its addresses, e.g. 30% of a /24 is the first /25. `percent` must be within 1..=100.
+    pub fn split_percent(&self, percent: u8) -> Result<Network, NetworkError> {
+        if !(1..=100).contains(&percent) {
+            return Err(NetworkError::InvalidHostsOrSubnets);
+        }
+        let needed = (self.address_count() * u64::from(percent)).div_ceil(100);
+        let prefix = 32 - needed.next_power_of_two().trailing_zeros();
+        Ok(Network::new(self.network_address(), prefix))
+    }
+
+    /// Carves two subnets sized for `hosts_a` and `hosts_b` out of this network, in that order.
+    pub fn split_for_two(
+        &self,
+        hosts_a: u32,
+        hosts_b: u32,
+    ) -> Result<(Network, Network), NetworkError> {
+        let mut allocator = VlsmAllocator::new(*self);
+        let a = allocator.allocate(hosts_a)?;
+        let b = allocator.allocate(hosts_b)?;
+        Ok((a, b))
+    }
+
+    /// Allocates each demand, in order, from whichever parent has the best-fitting free block,
+    /// returning the index of that parent with every subnet. Ties go to the earlier parent.
+    pub fn plan_vlsm_multi(
+        parents: &[Network],
+        demands: &[u32],
+    ) -> Result<Vec<(usize, Network)>, NetworkError> {
+        if parents.is_empty() {
+            return Err(NetworkError::EmptyNetworkList);
+        }
+        let mut allocators: Vec<VlsmAllocator> =
+            parents.iter().copied().map(VlsmAllocator::new).collect();
+        demands
+            .iter()
+            .map(|&hosts| {
+                let prefix = Self::prefix_for_hosts(hosts)?;
+                // The parent whose smallest sufficient free block is the smallest overall
+                let (index, _) = allocators
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, allocator)| {
+                        let fit = allocator
+                            .free_blocks()
+                            .iter()
+                            .map(|block| block.prefix)
+                            .filter(|&free| free <= prefix)
+                            .max()?;
+                        Some((index, fit))
+                    })
+                    .max_by_key(|&(index, fit)| (fit, Reverse(index)))
+                    .ok_or(NetworkError::InsufficientBits)?;
+                Ok((index, allocators[index].allocate(hosts)?))
+            })
+            .collect()
+    }
+
+    /// Draws a text bar of `parent` with each child's share marked by its position in the legend.
+    pub fn render_division(parent: &Network, children: &[Network]) -> String {
+        const WIDTH: u64 = 64;
+
+        let start = u64::from(u32::from(parent.network_address()));
+        let count = parent.address_count();
+        let column = |address: u64| ((address - start) * WIDTH / count) as usize;
+
+        let mut bar = vec![' '; WIDTH as usize + 1];
+        let mut legend = String::new();
+        for (i, child) in children
+            .iter()
+            .filter(|child| parent.contains_network(child))
+            .enumerate()
+        {
+            let first = column(u64::from(u32::from(child.network_address())));
+            let last =
+                column(u64::from(u32::from(child.network_address())) + child.address_count())
+                    .clamp(first + 1, WIDTH as usize);
+            let fill = if i % 2 == 0 { '=' } else { '-' };
+            bar[first] = '|';
+            bar[first + 1..last].fill(fill);
+            bar[last] = '|';
+
+            let label: Vec<char> = (i + 1).to_string().chars().collect();
+            if label.len() < last - first {
+                let offset = first + 1 + (last - first - 1 - label.len()) / 2;
+                bar[offset..offset + label.len()].copy_from_slice(&label);
+            }
+            legend.push_str(&format!(
+                "{:>4}: {} ({} - {})\n",
+                i + 1,
+                child,
+                child.network_address(),
+                child.broadcast_address()
+            ));
+        }
+
+        format!(
+            "{}\n{}\n{}",
+            parent,
+            bar.iter().collect::<String>().trim_end(),
+            legend
+        )
+    }
+
+    /// A self-contained HTML page with a table of the children and an SVG bar of how they
+    /// divide `parent`, children outside the parent are left out like in `render_division`.
+    pub fn render_html(parent: &Network, children: &[Network]) -> String {
+        const WIDTH: u64 = 640;
+        const COLORS: [&str; 4] = ["#4e79a7", "#f28e2b", "#59a14f", "#e15759"];
+
+        let start = u64::from(u32::from(parent.network_address()));
+        let count = parent.address_count();
+        let x = |address: u64| (address - start) * WIDTH / count;
+
+        let mut rows = String::new();
+        let mut rects = String::new();
+        for (i, child) in children
+            .iter()
+            .filter(|child| parent.contains_network(child))
+            .enumerate()
+        {
+            let (first, last) = child
+                .usable_range()
+                .map_or((String::from("-"), String::from("-")), |(first, last)| {
+                    (first.to_string(), last.to_string())
+                });
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                i + 1,
+                child,
+                child.netmask_address(),
+                first,
+                last,
+                child.available_hosts()
+            ));
+
+            let left = x(u64::from(u32::from(child.network_address())));
+            let right = x(u64::from(u32::from(child.network_address())) + child.address_count());
+            rects.push_str(&format!(
+                "<rect x=\"{}\" y=\"0\" width=\"{}\" height=\"32\" fill=\"{}\"><title>{}</title></rect>\n",
+                left,
+                (right - left).max(1),
+                COLORS[i % COLORS.len()],
+                child
+            ));
+        }
+
+        format!(
+            "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Subnet plan for {parent}</title>
+<style>
+body {{ font-family: sans-serif; }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; }}
+</style>
+</head>
+<body>
+<h1>Subnet plan for {parent}</h1>
+<svg width=\"{WIDTH}\" height=\"32\" xmlns=\"http://www.w3.org/2000/svg\">
+<rect x=\"0\" y=\"0\" width=\"{WIDTH}\" height=\"32\" fill=\"#eee\"/>
+{rects}</svg>
+<table>
+<tr><th>#</th><th>Subnet</th><th>Netmask</th><th>First Host</th><th>Last Host</th><th>Usable Hosts</th></tr>
+{rows}</table>
+</body>
+</html>
+"
+        )
+    }
+
+    /// In-place `summarize_networks`, the list ends up sorted, deduplicated and minimal.
+    pub fn coalesce(networks: &mut Vec<Network>) {
+        *networks = Self::summarize_networks(networks);
     }
 
     fn find_common_prefix(networks: &[Network]) -> u32 {
@@ -109,32 +1402,32 @@ impl Network {
             .count() as u32
     }
 
+    /// Positional shorthand for `SubnetPlan::builder()`, returning just the netmask. It shares
+    /// the builder's check, so hosts that don't fit next to the subnets now fail instead of
+    /// yielding a mask longer than the hosts allow.
     pub fn determine_subnet_mask(
         mask: u32,
         required_subnets: u32,
         required_hosts: u32,
     ) -> Result<Ipv4Addr, NetworkError> {
-        if required_hosts == 0 || required_subnets == 0 {
-            return Err(NetworkError::InvalidHostsOrSubnets);
-        }
-
-        let host_bits = (required_hosts + 2).next_power_of_two().trailing_zeros();
-        let subnet_bits = required_subnets.next_power_of_two().trailing_zeros();
-
-        info!(
-            "Required hosts: {}, Required subnets: {}, Host bits: {}, Subnet bits: {}",
-            required_hosts, required_subnets, host_bits, subnet_bits,
-        );
+        SubnetPlan::builder()
+            .base_prefix(mask)
+            .subnets(required_subnets)
+            .hosts(required_hosts)
+            .solve()
+            .map(|plan| plan.netmask)
+    }
 
-        if mask < host_bits || subnet_bits > 32 - mask {
-            return Err(NetworkError::InsufficientBits);
+    /// Longest prefix with at least `hosts` usable addresses, e.g. 50 hosts need a /26.
+    pub fn prefix_for_hosts(hosts: u32) -> Result<u32, NetworkError> {
+        if hosts == 0 {
+            return Err(NetworkError::InvalidHostsOrSubnets);
         }
-
-        let new_mask_prefix = mask + subnet_bits;
-        info!("New mask prefix: {}", new_mask_prefix);
-        let new_mask = Self::mask_to_u32(new_mask_prefix);
-
-        Ok(Ipv4Addr::from(new_mask.to_be_bytes()))
+        // Widened so the network and broadcast addresses can't overflow near 2^32
+        let host_bits = (u64::from(hosts) + 2).next_power_of_two().trailing_zeros();
+        32u32
+            .checked_sub(host_bits)
+            .ok_or(NetworkError::InsufficientBits)
     }
 
     pub fn default_mask(ip: Ipv4Addr) -> u32 {
@@ -146,7 +1439,84 @@ impl Network {
         }
     }
 
+    /// Start of the `/prefix` block containing `ip`, prefixes over 32 are treated as /32.
+    pub fn align_down(ip: Ipv4Addr, prefix: u32) -> Ipv4Addr {
+        Ipv4Addr::from(u32::from(ip) & Self::mask_to_u32(prefix.min(32)))
+    }
+
+    /// The first `/prefix` boundary at or after `ip`, `None` when it would be past the end of
+    /// the address space.
+    pub fn align_up(ip: Ipv4Addr, prefix: u32) -> Option<Ipv4Addr> {
+        let down = Self::align_down(ip, prefix);
+        if down == ip {
+            return Some(ip);
+        }
+        let next = u64::from(u32::from(down)) + (1u64 << (32 - prefix.min(32)));
+        u32::try_from(next).ok().map(Ipv4Addr::from)
+    }
+
+    /// Converts a dotted netmask such as `255.255.255.192` to its prefix length.
+    pub fn prefix_from_netmask(netmask: Ipv4Addr) -> Result<u32, NetworkError> {
+        let mask = u32::from(netmask);
+        // A contiguous mask is a run of ones followed only by zeros
+        if mask.leading_ones() + mask.trailing_zeros() != 32 {
+            return Err(NetworkError::NonContiguousMask);
+        }
+        Ok(mask.leading_ones())
+    }
+
+    /// Accepts either a prefix length (`24`) or a dotted netmask (`255.255.255.0`).
+    fn parse_mask(mask_str: &str) -> Result<u32, NetworkError> {
+        if mask_str.contains('.') {
+            let netmask = mask_str
+                .parse::<Ipv4Addr>()
+                .map_err(|_| NetworkError::InvalidMaskFormat)?;
+            return Self::prefix_from_netmask(netmask);
+        }
+        let prefix = mask_str
+            .parse::<u32>()
+            .map_err(|_| NetworkError::InvalidMaskFormat)?;
+        if prefix > 32 {
+            return Err(NetworkError::InvalidPrefixLength);
+        }
+        Ok(prefix)
+    }
+
+    /// Reads the digits after `0x` as a 32-bit address, exactly eight are required.
+    fn parse_hex_ip(hex: &str) -> Result<Ipv4Addr, NetworkError> {
+        if hex.len() != 8 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(NetworkError::InvalidIpFormat);
+        }
+        u32::from_str_radix(hex, 16)
+            .map(Ipv4Addr::from)
+            .map_err(|_| NetworkError::InvalidIpFormat)
+    }
+
+    /// Pinpoints why an address failed to parse, falling back to a generic format error.
+    /// Names the offending octet when one is out of range or the address is IPv6, otherwise
+    /// keeps the parser's reason.
+    fn diagnose_ip(ip_str: &str, error: AddrParseError) -> NetworkError {
+        if ip_str.parse::<Ipv6Addr>().is_ok() {
+            return NetworkError::UnsupportedIpv6;
+        }
+        let octets: Vec<&str> = ip_str.split('.').collect();
+        let out_of_range = octets.iter().find(|octet| {
+            !octet.is_empty()
+                && octet.bytes().all(|b| b.is_ascii_digit())
+                && octet.parse::<u8>().is_err()
+        });
+        match out_of_range {
+            Some(octet) if octets.len() == 4 => NetworkError::InvalidOctet(octet.to_string()),
+            _ => error.into(),
+        }
+    }
+
     pub fn mask_to_u32(mask: u32) -> u32 {
-        !0 << (32 - mask)
+        // A shift by the full width overflows, /0 has no network bits at all
+        u32::MAX.checked_shl(32 - mask).unwrap_or(0)
+    }
+
+    fn checked_mask(prefix: u32) -> Option<u32> {
+        (prefix <= 32).then(|| Self::mask_to_u32(prefix))
     }
 }
@@ -0,0 +1,50 @@
+use crate::errors::NetworkError;
+use crate::subnet::Network;
+
+/// A network carrying caller metadata, e.g. an IPAM label, through aggregation and summarization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaggedNetwork<T> {
+    pub network: Network,
+    pub tag: T,
+}
+
+/// A network produced from tagged members, holding the tags of every member it covers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaggedAggregate<T> {
+    pub network: Network,
+    pub tags: Vec<T>,
+}
+
+impl<T: Clone> TaggedNetwork<T> {
+    pub fn new(network: Network, tag: T) -> Self {
+        Self { network, tag }
+    }
+
+    /// Same as `Network::aggregate_networks`, the aggregate lists every member tag in input order.
+    pub fn aggregate(networks: &[TaggedNetwork<T>]) -> Result<TaggedAggregate<T>, NetworkError> {
+        let network = Network::aggregate_networks(&Self::untagged(networks))?;
+        Ok(TaggedAggregate {
+            network,
+            tags: networks.iter().map(|member| member.tag.clone()).collect(),
+        })
+    }
+
+    /// Same as `Network::summarize_networks`, each result lists the tags of the members it contains.
+    pub fn summarize(networks: &[TaggedNetwork<T>]) -> Vec<TaggedAggregate<T>> {
+        Network::summarize_networks(&Self::untagged(networks))
+            .into_iter()
+            .map(|network| TaggedAggregate {
+                network,
+                tags: networks
+                    .iter()
+                    .filter(|member| network.contains_network(&member.network))
+                    .map(|member| member.tag.clone())
+                    .collect(),
+            })
+            .collect()
+    }
+
+    fn untagged(networks: &[TaggedNetwork<T>]) -> Vec<Network> {
+        networks.iter().map(|member| member.network).collect()
+    }
+}
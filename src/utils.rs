@@ -0,0 +1,62 @@
+use crate::errors::NetworkError;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Builds the reverse pointer name for a single address, e.g. `1.2.3.4` -> `4.3.2.1.in-addr.arpa`.
+pub fn ipv4_to_arpa(ip: Ipv4Addr) -> String {
+    let [a, b, c, d] = ip.octets();
+    format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+}
+
+/// The address after `ip`, `None` past 255.255.255.255.
+pub fn next_addr(ip: Ipv4Addr) -> Option<Ipv4Addr> {
+    u32::from(ip).checked_add(1).map(Ipv4Addr::from)
+}
+
+/// The address before `ip`, `None` before 0.0.0.0.
+pub fn prev_addr(ip: Ipv4Addr) -> Option<Ipv4Addr> {
+    u32::from(ip).checked_sub(1).map(Ipv4Addr::from)
+}
+
+/// Groups thousands for readability, e.g. `16777214` -> `16,777,214`.
+pub fn humanize_count(n: u64) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Short approximate form truncated to one decimal, e.g. `16777214` -> `~16.7M`.
+pub fn approximate_count(n: u64) -> String {
+    const UNITS: [(u64, &str); 3] = [(1_000_000_000, "B"), (1_000_000, "M"), (1_000, "K")];
+    match UNITS.iter().find(|(scale, _)| n >= *scale) {
+        Some((scale, unit)) => format!("~{}.{}{}", n / scale, n % scale * 10 / scale, unit),
+        None => n.to_string(),
+    }
+}
+
+/// Builds the modified EUI-64 address for `mac` under a /64 `prefix`: `ff:fe` is inserted
+/// in the middle of the MAC and the universal/local bit is flipped.
+pub fn eui64_from_mac(prefix: Ipv6Addr, mac: [u8; 6]) -> Result<Ipv6Addr, NetworkError> {
+    let prefix = u128::from(prefix);
+    if prefix & u128::from(u64::MAX) != 0 {
+        return Err(NetworkError::MisalignedPrefix);
+    }
+    let interface_id = [
+        mac[0] ^ 0x02,
+        mac[1],
+        mac[2],
+        0xff,
+        0xfe,
+        mac[3],
+        mac[4],
+        mac[5],
+    ];
+    Ok(Ipv6Addr::from(
+        prefix | u128::from(u64::from_be_bytes(interface_id)),
+    ))
+}
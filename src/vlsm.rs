@@ -0,0 +1,41 @@
+use crate::errors::NetworkError;
+use crate::subnet::Network;
+use log::info;
+use std::cmp::Reverse;
+
+/// Incrementally carves subnets sized for host demands out of a parent network.
+#[derive(Debug, Clone)]
+pub struct VlsmAllocator {
+    free: Vec<Network>,
+}
+
+impl VlsmAllocator {
+    pub fn new(parent: Network) -> Self {
+        Self {
+            free: vec![Network::new(parent.network_address(), parent.prefix)],
+        }
+    }
+
+    /// Allocates the smallest subnet with at least `hosts` usable addresses from the
+    /// best-fitting free block, lowest address first.
+    pub fn allocate(&mut self, hosts: u32) -> Result<Network, NetworkError> {
+        let prefix = Network::prefix_for_hosts(hosts)?;
+        let (index, _) = self
+            .free
+            .iter()
+            .enumerate()
+            .filter(|(_, block)| block.prefix <= prefix)
+            .min_by_key(|(_, block)| (Reverse(block.prefix), u32::from(block.ip)))
+            .ok_or(NetworkError::InsufficientBits)?;
+
+        let (block, remainder) = self.free.remove(index).split_off_first(prefix)?;
+        self.free.extend(remainder);
+        info!("Allocated {} for {} hosts", block, hosts);
+
+        Ok(block)
+    }
+
+    pub fn free_blocks(&self) -> &[Network] {
+        &self.free
+    }
+}
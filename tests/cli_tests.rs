@@ -0,0 +1,1178 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn subnetcalc(args: &[&str]) -> Output {
+    subnetcalc_with_env(args, &[])
+}
+
+fn subnetcalc_with_env(args: &[&str], vars: &[(&str, &str)]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cli"))
+        .args(args)
+        .envs(vars.iter().copied())
+        .output()
+        .expect("Failed to run subnetcalc")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("subnetcalc-{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).expect("Failed to write temp file");
+    path
+}
+
+#[test]
+fn test_aggregate_show_members_flags_uncontained() {
+    let output = subnetcalc(&[
+        "aggregate",
+        "--show-members",
+        "192.168.0.0/16",
+        "192.168.1.0/24",
+    ]);
+    assert!(output.status.success());
+
+    let stdout = stdout(&output);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "Aggregated Network: 192.168.0.0/23",
+            "  192.168.0.0/16 (not contained)",
+            "  192.168.1.0/24",
+            "combined 2 networks into 1 (/23), saving 1 table entry",
+        ]
+    );
+}
+
+#[test]
+fn test_aggregate_show_members_rejects_non_text_formats() {
+    let output = subnetcalc_with_env(
+        &["aggregate", "--show-members", "10.0.0.0/24", "10.0.1.0/24"],
+        &[("SUBNETCALC_FORMAT", "json")],
+    );
+    assert!(!output.status.success());
+    assert!(stdout(&output).is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--show-members only reports as text"));
+
+    let output = subnetcalc(&[
+        "--format",
+        "csv",
+        "aggregate",
+        "--show-members",
+        "10.0.0.0/24",
+        "2001:db8::/48",
+    ]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).is_empty());
+}
+
+#[test]
+fn test_summarize_json_array() {
+    let output = subnetcalc(&[
+        "summarize",
+        "--json",
+        "10.0.0.0/24",
+        "10.0.1.0/24",
+        "10.0.4.0/24",
+    ]);
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let blocks = json.as_array().unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0]["network"], "10.0.0.0");
+    assert_eq!(blocks[0]["prefix"], 23);
+    assert_eq!(blocks[0]["netmask"], "255.255.254.0");
+    assert_eq!(blocks[1]["broadcast"], "10.0.4.255");
+}
+
+#[test]
+fn test_aggregate_json_array() {
+    let output = subnetcalc(&["aggregate", "--json", "10.0.0.0/24", "10.0.1.0/24"]);
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let blocks = json.as_array().unwrap();
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0]["network"], "10.0.0.0");
+    assert_eq!(blocks[0]["prefix"], 23);
+}
+
+#[test]
+fn test_compare_nested() {
+    let output = subnetcalc(&["compare", "10.0.1.0/24", "10.0.0.0/16"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Relationship: 10.0.0.0/16 contains 10.0.1.0/24\nSize Difference: 65280 addresses\n"
+    );
+}
+
+#[test]
+fn test_compare_disjoint() {
+    let output = subnetcalc(&["compare", "10.0.0.0/24", "10.1.0.0/24"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Relationship: 10.0.0.0/24 and 10.1.0.0/24 are disjoint\nSize Difference: 0 addresses\n"
+    );
+}
+
+#[test]
+fn test_info_policy() {
+    let output = subnetcalc(&["info", "10.0.0.1/32", "--policy", "rfc3021"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Available Hosts: 1\n"));
+
+    let output = subnetcalc(&["info", "10.0.0.1/32"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Available Hosts: 0\n"));
+}
+
+#[test]
+fn test_info_special_class_note() {
+    let output = subnetcalc(&["info", "127.0.0.1/8"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Class: A (loopback)\n"));
+}
+
+#[test]
+fn test_reference_table() {
+    let output = subnetcalc(&["reference"]);
+    assert!(output.status.success());
+
+    let stdout = stdout(&output);
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .filter(|line| line.starts_with('/'))
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert_eq!(rows.len(), 33);
+    assert_eq!(
+        rows[24],
+        vec!["/24", "255.255.255.0", "0.0.0.255", "256", "254"]
+    );
+    assert_eq!(
+        rows[0],
+        vec![
+            "/0",
+            "0.0.0.0",
+            "255.255.255.255",
+            "4294967296",
+            "4294967294"
+        ]
+    );
+    assert_eq!(
+        rows[32],
+        vec!["/32", "255.255.255.255", "0.0.0.0", "1", "0"]
+    );
+}
+
+#[test]
+fn test_info_point_to_point() {
+    let output = subnetcalc(&["info", "10.0.0.0/31"]);
+    assert!(output.status.success());
+
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Broadcast: none (point-to-point)\n"));
+    assert!(stdout.contains("Available Hosts: 2\n"));
+
+    let output = subnetcalc(&["info", "10.0.0.0/31", "--json"]);
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(info["broadcast"], serde_json::Value::Null);
+    assert_eq!(info["first_host"], "10.0.0.0");
+    assert_eq!(info["last_host"], "10.0.0.1");
+    assert_eq!(info["available_hosts"], 2);
+}
+
+#[test]
+fn test_split_ascii() {
+    let output = subnetcalc(&["split", "192.168.1.0/24", "--into", "2", "--ascii"]);
+    assert!(output.status.success());
+
+    let stdout = stdout(&output);
+    assert!(stdout.contains("1: 192.168.1.0/25 (192.168.1.0 - 192.168.1.127)"));
+    assert!(stdout.contains("2: 192.168.1.128/25 (192.168.1.128 - 192.168.1.255)"));
+}
+
+#[test]
+fn test_split_too_many_fails() {
+    let output = subnetcalc(&["split", "10.0.0.0/30", "--into", "8"]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Error: Insufficient bits"));
+}
+
+#[test]
+fn test_split_over_limit_fails() {
+    let output = subnetcalc(&["split", "0.0.0.0/0", "--into", "2147483648"]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: The network has too many addresses"));
+}
+
+#[test]
+fn test_split_warn_unaligned() {
+    let output = subnetcalc(&["split", "192.168.0.0/16", "--into", "2", "--warn-unaligned"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("  192.168.0.0/17 (boundary inside octet 3)\n"));
+
+    let output = subnetcalc(&[
+        "split",
+        "192.168.0.0/16",
+        "--into",
+        "256",
+        "--warn-unaligned",
+    ]);
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    assert!(stdout.contains("  192.168.1.0/24\n"));
+    assert!(!stdout.contains("boundary inside"));
+}
+
+#[test]
+fn test_aggregate_min_prefix() {
+    let output = subnetcalc(&[
+        "aggregate",
+        "--min-prefix",
+        "8",
+        "10.0.0.0/8",
+        "192.168.0.0/16",
+    ]);
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "");
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: The aggregate is shorter than the minimum allowed prefix.\n"));
+}
+
+#[test]
+fn test_info_misaligned_suggestion() {
+    let output = subnetcalc(&["info", "192.168.1.37/24"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("did you mean 192.168.1.0/24?\n"));
+
+    let output = subnetcalc(&["info", "192.168.1.0/24"]);
+    assert!(!stdout(&output).contains("did you mean"));
+}
+
+#[test]
+fn test_diff_files() {
+    let old = temp_file("diff-old.txt", "10.0.0.0/24\n10.0.1.0/24\n");
+    let new = temp_file("diff-new.txt", "# updated\n10.0.1.0/24\n\n10.0.2.0/24\n");
+
+    let output = subnetcalc(&[
+        "diff",
+        "--old",
+        old.to_str().unwrap(),
+        "--new",
+        new.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "+ 10.0.2.0/24\n- 10.0.0.0/24\n");
+}
+
+#[test]
+fn test_info_quiet() {
+    let output = subnetcalc(&["info", "10.0.0.0/24", "--quiet"]);
+    assert!(output.status.success());
+
+    let stdout = stdout(&output);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "10.0.0.0/24",
+            "255.255.255.0",
+            "0.0.0.255",
+            "10.0.0.255",
+            "254",
+            "A"
+        ]
+    );
+}
+
+#[test]
+fn test_hosts_count_only() {
+    let output = subnetcalc(&["hosts", "192.168.1.0/28", "--count-only"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "14\n");
+}
+
+#[test]
+fn test_hosts_listing() {
+    let output = subnetcalc(&["hosts", "192.168.1.0/30"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "192.168.1.1\n192.168.1.2\n");
+}
+
+#[test]
+fn test_info_humanized_hosts() {
+    let output = subnetcalc(&["info", "10.0.0.0/8"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Available Hosts: 16,777,214 (~16.7M)\n"));
+}
+
+#[test]
+fn test_format_from_env() {
+    let output = subnetcalc_with_env(&["info", "10.0.0.0/24"], &[("SUBNETCALC_FORMAT", "json")]);
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(json["network"], "10.0.0.0");
+    assert_eq!(json["broadcast"], "10.0.0.255");
+}
+
+#[test]
+fn test_format_flag_overrides_env() {
+    let output = subnetcalc_with_env(
+        &["info", "10.0.0.0/24", "--format", "text"],
+        &[("SUBNETCALC_FORMAT", "json")],
+    );
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Network: 10.0.0.0/24\n"));
+}
+
+#[test]
+fn test_format_csv() {
+    let output = subnetcalc(&["summarize", "--format", "csv", "10.0.0.0/24", "10.0.1.0/24"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "network,prefix,netmask,wildcard,broadcast,available_hosts,class\n\
+         10.0.0.0,23,255.255.254.0,0.0.1.255,10.0.1.255,510,A\n"
+    );
+}
+
+#[test]
+fn test_aggregate_comma_separated() {
+    let separate = subnetcalc(&["aggregate", "10.0.0.0/24", "10.0.1.0/24"]);
+    let comma = subnetcalc(&["aggregate", "10.0.0.0/24,10.0.1.0/24"]);
+    assert!(comma.status.success());
+    assert_eq!(stdout(&comma), stdout(&separate));
+    assert_eq!(
+        stdout(&comma),
+        "Aggregated Network: 10.0.0.0/23\ncombined 2 networks into 1 (/23), saving 1 table entry\n"
+    );
+
+    let mixed = subnetcalc(&["aggregate", "10.0.0.0/24, 10.0.1.0 / 24"]);
+    assert_eq!(stdout(&mixed), stdout(&separate));
+}
+
+#[test]
+fn test_validate_bad_octet() {
+    let output = subnetcalc(&["validate", "10.0.0.0/24", "300.1.1.1/24"]);
+    assert!(!output.status.success());
+
+    let stdout = stdout(&output);
+    assert!(stdout.contains("10.0.0.0/24: valid\n"));
+    assert!(stdout.contains(
+        "300.1.1.1/24: invalid (Invalid octet '300', octets must be between 0 and 255.)\n"
+    ));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: Invalid format: 1 of 2 networks are invalid"));
+}
+
+#[test]
+fn test_validate_prefix_out_of_range() {
+    let output = subnetcalc(&["validate", "10.0.0.0/40"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "10.0.0.0/40: invalid (Prefix length must be between 0 and 32.)\n"
+    );
+
+    let output = subnetcalc(&["validate", "10.0.0.0/8"]);
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_expand_guarded() {
+    let output = subnetcalc(&["expand", "10.0.0.0/8"]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: The network has too many addresses to expand."));
+
+    let output = subnetcalc(&["expand", "10.0.0.0/15", "--force"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output).lines().count(), 131_072);
+}
+
+#[test]
+fn test_mask_subnets_then_hosts() {
+    let output = subnetcalc(&["mask", "192.168.0.0/16", "320", "90"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "Subnet Mask: 255.255.255.128\n");
+}
+
+#[test]
+fn test_info_prefix_only() {
+    let output = subnetcalc(&["info", "0.0.0.0/255.255.255.192", "--prefix-only"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "26\n");
+}
+
+#[test]
+fn test_info_oneline() {
+    let output = subnetcalc(&["info", "10.1.2.0/26", "--oneline"]);
+    assert_eq!(
+        stdout(&output),
+        "10.1.2.0/26 (255.255.255.192, 62 hosts, class A)\n"
+    );
+}
+
+#[test]
+fn test_output_file() {
+    let path = temp_file("aggregate-output.txt", "");
+    let output = subnetcalc_with_env(
+        &[
+            "aggregate",
+            "192.168.0.0/24",
+            "192.168.1.0/24",
+            "--output",
+            path.to_str().unwrap(),
+        ],
+        &[("CLICOLOR_FORCE", "1")],
+    );
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "Aggregated Network: 192.168.0.0/23\ncombined 2 networks into 1 (/23), saving 1 table entry\n"
+    );
+}
+
+#[test]
+fn test_output_file_kept_when_command_fails() {
+    let path = temp_file("aggregate-kept-output.txt", "previous run\n");
+    let output = subnetcalc(&[
+        "--output",
+        path.to_str().unwrap(),
+        "aggregate",
+        "10.0.0.0/33",
+    ]);
+    assert!(!output.status.success());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "previous run\n");
+    // The staging file is cleaned up rather than left next to the target
+    let staged = path.with_file_name(".aggregate-kept-output.txt.partial");
+    assert!(!staged.exists());
+
+    let missing = path.with_file_name("aggregate-never-created.txt");
+    let _ = std::fs::remove_file(&missing);
+    let output = subnetcalc(&[
+        "--output",
+        missing.to_str().unwrap(),
+        "aggregate",
+        "10.0.0.0/33",
+    ]);
+    assert!(!output.status.success());
+    assert!(!missing.exists());
+}
+
+#[test]
+fn test_theme_mono_has_no_escape_codes() {
+    let force_color = [("CLICOLOR_FORCE", "1")];
+    let output = subnetcalc_with_env(&["info", "10.0.0.1/24"], &force_color);
+    assert!(stdout(&output).contains('\x1b'));
+
+    for args in [
+        &["--theme", "mono", "info", "10.0.0.1/24"][..],
+        &["--theme", "mono", "aggregate", "10.0.0.0/24", "10.0.1.0/24"],
+        &["--theme", "mono", "mask", "192.168.0.0/16", "320", "90"],
+        &["--theme", "mono", "summarize", "10.0.0.0/24", "10.0.1.0/24"],
+    ] {
+        let output = subnetcalc_with_env(args, &force_color);
+        assert!(output.status.success());
+        assert!(!stdout(&output).contains('\x1b'));
+    }
+
+    let output = subnetcalc_with_env(
+        &["info", "10.0.0.1/24"],
+        &[("CLICOLOR_FORCE", "1"), ("SUBNETCALC_THEME", "mono")],
+    );
+    assert!(!stdout(&output).contains('\x1b'));
+}
+
+#[test]
+fn test_info_bare_prefix() {
+    let expected =
+        "Netmask: 255.255.255.252\nWildcard: 0.0.0.3\nAddresses: 4\nAvailable Hosts: 2\n";
+    let output = subnetcalc(&["info", "/30"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), expected);
+
+    let output = subnetcalc(&["info", "30"]);
+    assert_eq!(stdout(&output), expected);
+
+    let output = subnetcalc(&["info", "/32", "--policy", "rfc3021"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Available Hosts: 1\n"));
+}
+
+#[test]
+fn test_info_bare_prefix_rejects_other_views_and_formats() {
+    for args in [
+        &["info", "26", "--json"][..],
+        &["info", "/26", "--quiet"],
+        &["info", "/26", "--oneline"],
+        &["--format", "csv", "info", "/26"],
+    ] {
+        let output = subnetcalc(args);
+        assert!(!output.status.success(), "{:?} should fail", args);
+        assert!(stdout(&output).is_empty());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Error: Invalid format"));
+    }
+}
+
+#[test]
+fn test_plan_demands_file() {
+    let demands = temp_file("plan-demands.txt", "100\n50\n25\n");
+    let output = subnetcalc(&[
+        "plan",
+        "192.168.1.0/24",
+        "--demands-file",
+        demands.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "192.168.1.0/25 (100 hosts)\n192.168.1.128/26 (50 hosts)\n192.168.1.192/27 (25 hosts)\n"
+    );
+
+    let demands = temp_file("plan-demands-overflow.txt", "100\n200\n");
+    let output = subnetcalc(&[
+        "plan",
+        "192.168.1.0/24",
+        "--demands-file",
+        demands.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: cannot fit 100 hosts in 192.168.1.0/24"));
+}
+
+#[test]
+fn test_width_limits_tables() {
+    for args in [
+        &["--width", "40", "info", "10.0.0.0/24", "--format", "table"][..],
+        &["--width", "40", "reference"],
+    ] {
+        let output = subnetcalc(args);
+        assert!(output.status.success());
+        let stdout = stdout(&output);
+        assert!(stdout.lines().count() > 1);
+        assert!(stdout.lines().all(|line| line.chars().count() <= 40));
+    }
+
+    // Without a terminal or --width the table is left alone
+    let output = subnetcalc(&["info", "10.0.0.0/24", "--format", "table"]);
+    assert!(stdout(&output)
+        .lines()
+        .any(|line| line.chars().count() > 80));
+}
+
+#[test]
+fn test_report_html() {
+    let output = subnetcalc(&[
+        "report",
+        "192.168.1.0/24",
+        "--demands",
+        "100,50,25",
+        "--html",
+    ]);
+    assert!(output.status.success());
+    let html = stdout(&output);
+    assert!(html.contains("<table>"));
+    for subnet in ["192.168.1.0/25", "192.168.1.128/26", "192.168.1.192/27"] {
+        assert!(html.contains(subnet));
+    }
+}
+
+#[test]
+fn test_combined_summary_line() {
+    let networks = ["10.0.0.0/24", "10.0.1.0/24", "10.0.2.0/23"];
+    let output = subnetcalc(&[&["aggregate"][..], &networks].concat());
+    assert_eq!(
+        stdout(&output),
+        "Aggregated Network: 10.0.0.0/22\ncombined 3 networks into 1 (/22), saving 2 table entries\n"
+    );
+
+    let output = subnetcalc(&[&["aggregate", "--quiet"][..], &networks].concat());
+    assert_eq!(stdout(&output), "Aggregated Network: 10.0.0.0/22\n");
+
+    let output = subnetcalc(&["summarize", "10.0.0.0/24", "10.0.1.0/24", "10.0.4.0/24"]);
+    assert!(stdout(&output).ends_with("combined 3 networks into 2\n"));
+}
+
+#[test]
+fn test_default_prefix_env() {
+    let output = subnetcalc_with_env(
+        &["info", "10.0.0.5", "--prefix-only"],
+        &[("SUBNETCALC_DEFAULT_PREFIX", "32")],
+    );
+    assert_eq!(stdout(&output), "32\n");
+
+    let output = subnetcalc(&[
+        "info",
+        "10.0.0.5",
+        "--prefix-only",
+        "--default-prefix",
+        "24",
+    ]);
+    assert_eq!(stdout(&output), "24\n");
+
+    let output = subnetcalc(&["info", "10.0.0.5", "--prefix-only"]);
+    assert_eq!(stdout(&output), "8\n");
+}
+
+#[test]
+fn test_filter_ips() {
+    let ips = ["10.1.2.3", "192.168.1.1", "10.255.0.1", "11.0.0.1"];
+    let output = subnetcalc(&[&["filter", "10.0.0.0/8"][..], &ips].concat());
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "10.1.2.3\n10.255.0.1\n");
+
+    let output = subnetcalc(&[&["filter", "10.0.0.0/8", "--invert"][..], &ips].concat());
+    assert_eq!(stdout(&output), "192.168.1.1\n11.0.0.1\n");
+}
+
+#[test]
+fn test_filter_stdin() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_cli"))
+        .args(["filter", "192.168.1.0/24"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"192.168.1.10\n10.0.0.1\n192.168.1.20\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(stdout(&output), "192.168.1.10\n192.168.1.20\n");
+}
+
+#[test]
+fn test_allocate_next_free() {
+    let used = temp_file("allocate-used.txt", "# taken\n192.168.1.0/26\n");
+    let output = subnetcalc(&[
+        "allocate",
+        "192.168.1.0/24",
+        "--prefix",
+        "26",
+        "--used",
+        used.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "192.168.1.64/26\n");
+
+    let used = temp_file("allocate-full.txt", "192.168.1.0/24\n");
+    let result = temp_file("allocate-full-output.txt", "10.0.0.0/26\n");
+    let output = subnetcalc(&[
+        "--output",
+        result.to_str().unwrap(),
+        "allocate",
+        "192.168.1.0/24",
+        "--prefix",
+        "26",
+        "--used",
+        used.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).is_empty());
+    assert_eq!(std::fs::read_to_string(&result).unwrap(), "10.0.0.0/26\n");
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: No free block of the requested size is left."));
+}
+
+#[test]
+fn test_allocate_ignores_used_networks_outside_parent() {
+    let used = temp_file("allocate-unrelated.txt", "192.168.0.0/24\n10.0.0.0/26\n");
+    let output = subnetcalc(&[
+        "allocate",
+        "10.0.0.0/24",
+        "--prefix",
+        "26",
+        "--used",
+        used.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "10.0.0.64/26\n");
+
+    let used = temp_file("allocate-covering.txt", "10.0.0.0/8\n");
+    let output = subnetcalc(&[
+        "allocate",
+        "10.0.0.0/24",
+        "--prefix",
+        "26",
+        "--used",
+        used.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: No free block of the requested size is left."));
+}
+
+#[test]
+fn test_info_host_networks_at_address_space_edges() {
+    for ip in ["255.255.255.255", "0.0.0.0"] {
+        let output = subnetcalc(&["info", &format!("{}/32", ip)]);
+        assert!(output.status.success());
+        let text = stdout(&output);
+        assert!(text.contains(&format!("Network: {}/32", ip)));
+        assert!(text.contains(&format!("Broadcast: {}", ip)));
+        assert!(text.contains("Available Hosts: 0"));
+    }
+}
+
+#[test]
+fn test_hosts_stops_quietly_on_broken_pipe() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_cli"))
+        .args(["hosts", "10.0.0.0/8"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run subnetcalc");
+    let mut reader = BufReader::new(child.stdout.take().unwrap());
+    let mut first = String::new();
+    reader.read_line(&mut first).unwrap();
+    assert_eq!(first, "10.0.0.1\n");
+    // Stop reading like `head` would, the next writes hit a closed pipe
+    drop(reader);
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("Broken pipe"));
+}
+
+#[test]
+fn test_examples_info() {
+    let output = subnetcalc(&["examples", "info"]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.starts_with("$ subnetcalc info 192.168.1.0/26\n"));
+    assert!(text.contains("Broadcast: 192.168.1.63\n"));
+    assert!(!text.contains("aggregate"));
+}
+
+#[test]
+fn test_examples_all_succeed() {
+    let output = subnetcalc(&["examples"]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert_eq!(text.matches("$ subnetcalc ").count(), 5);
+    assert!(!text.contains("Error"));
+}
+
+#[test]
+fn test_delimiter() {
+    let output = subnetcalc(&["hosts", "10.0.0.0/30", "--delimiter", ","]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "10.0.0.1,10.0.0.2\n");
+
+    let output = subnetcalc(&["hosts", "10.0.0.0/30", "--delimiter", "\\0"]);
+    assert_eq!(stdout(&output), "10.0.0.1\x0010.0.0.2\x00");
+
+    let output = subnetcalc(&[
+        "summarize",
+        "10.0.0.0/24",
+        "10.0.1.0/24",
+        "10.0.4.0/24",
+        "--delimiter",
+        "\\t",
+    ]);
+    assert_eq!(stdout(&output), "10.0.0.0/23\t10.0.4.0/24\n");
+
+    let output = subnetcalc(&[
+        "filter",
+        "10.0.0.0/8",
+        "10.1.1.1",
+        "192.168.0.1",
+        "10.2.2.2",
+        "--delimiter",
+        ",",
+    ]);
+    assert_eq!(stdout(&output), "10.1.1.1,10.2.2.2\n");
+
+    let output = subnetcalc(&["hosts", "10.0.0.0/30", "--delimiter", "ab"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_aggregate_warns_when_spanning_classful_blocks() {
+    let output = subnetcalc(&["aggregate", "10.0.0.0/8", "11.0.0.0/8"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Aggregated Network: 10.0.0.0/7\n"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(
+        "warning: 10.0.0.0/7 is shorter than the classful /8 and spans 2 class A blocks"
+    ));
+
+    let output = subnetcalc(&["aggregate", "10.0.0.0/24", "10.0.1.0/24"]);
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("warning"));
+}
+
+#[test]
+fn test_aggregate_and_mask_errors_exit_nonzero() {
+    let output = subnetcalc(&["aggregate", ","]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Error: The network list is empty."));
+
+    let output = subnetcalc(&["mask", "192.168.1.0/24", "4", "100"]);
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "");
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: Insufficient bits available for the required subnets or hosts."));
+}
+
+#[test]
+fn test_summarize_summary_only() {
+    let output = subnetcalc(&[
+        "summarize",
+        "--summary-only",
+        "10.0.0.0/24",
+        "10.0.1.0/24",
+        "10.0.4.0/24",
+        "192.168.0.0/16",
+    ]);
+    assert!(output.status.success());
+    // 10.0.0.0/23, 10.0.4.0/24 and 192.168.0.0/16
+    assert_eq!(stdout(&output), "Blocks: 3\nAddresses: 66304\n");
+}
+
+#[test]
+fn test_aggregate_names_the_bad_entry() {
+    let output = subnetcalc(&["aggregate", "10.0.0.0/24,10.0.1.0/33"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: `10.0.1.0/33`: Prefix length must be between 0 and 32."));
+}
+
+#[test]
+fn test_mask_json_and_quiet() {
+    let output = subnetcalc(&["mask", "192.168.0.0/16", "320", "90", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "prefix": 25,
+            "netmask": "255.255.255.128",
+            "usable_hosts": 126,
+            "subnets": 512
+        })
+    );
+
+    let output = subnetcalc(&["mask", "192.168.0.0/16", "320", "90", "--quiet"]);
+    assert_eq!(stdout(&output), "255.255.255.128\n");
+}
+
+#[test]
+fn test_fit_hosts() {
+    let output = subnetcalc(&["fit", "--hosts", "62"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Prefix: /26\nNetmask: 255.255.255.192\nUsable Hosts: 62\nWasted Addresses: 0\n"
+    );
+
+    let output = subnetcalc(&["fit", "--hosts", "100"]);
+    let text = stdout(&output);
+    assert!(text.contains("Prefix: /25\n"));
+    assert!(text.contains("Wasted Addresses: 26\n"));
+}
+
+#[test]
+fn test_ipv6_info_and_aggregate() {
+    let output = subnetcalc(&["info", "2001:db8::/48"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Network: 2001:db8::/48\n\
+         Netmask: ffff:ffff:ffff::\n\
+         Last Address: 2001:db8:0:ffff:ffff:ffff:ffff:ffff\n\
+         Available Hosts: 2^80\n"
+    );
+
+    let output = subnetcalc(&["info", "--json", "2001:db8::/120"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(json["last_address"], "2001:db8::ff");
+    assert_eq!(json["available_hosts"], 256);
+
+    let output = subnetcalc(&["aggregate", "2001:db8::/48", "2001:db8:1::/48"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Aggregated Network: 2001:db8::/47\n\
+         combined 2 networks into 1 (/47), saving 1 table entry\n"
+    );
+}
+
+#[test]
+fn test_ipv6_unsupported_options() {
+    let output = subnetcalc(&["info", "::/0", "--quiet"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "::/0\n::\nffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff\n2^128\n"
+    );
+
+    let output = subnetcalc(&["info", "2001:db8::/129"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: IPv6 prefix length must be between 0 and 128."));
+
+    let output = subnetcalc(&["info", "2001:db8::/64", "--policy", "rfc3021"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--policy only applies to IPv4"));
+
+    for format in ["csv", "table"] {
+        let output = subnetcalc(&["--format", format, "info", "2001:db8::/48"]);
+        assert!(!output.status.success());
+        assert!(stdout(&output).is_empty());
+        assert!(String::from_utf8_lossy(&output.stderr)
+            .contains("Error: Invalid format: IPv6 networks have no csv or table layout"));
+    }
+
+    let output = subnetcalc(&["mask", "2001:db8::/32", "4", "100"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: IPv6 networks are not supported here, only IPv4."));
+}
+
+#[test]
+fn test_aggregate_mixed_families() {
+    let output = subnetcalc(&[
+        "aggregate",
+        "10.0.0.0/24,2001:db8::/48",
+        "10.0.1.0/24",
+        "2001:db8:1::/48",
+        "10.0.2.0/24",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Aggregated Network: 10.0.0.0/22\n\
+         combined 3 networks into 1 (/22), saving 2 table entries\n\
+         Aggregated Network: 2001:db8::/47\n\
+         combined 2 networks into 1 (/47), saving 1 table entry\n"
+    );
+
+    let output = subnetcalc(&["aggregate", "--json", "10.0.0.0/24", "2001:db8::/48"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let records = json.as_array().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["broadcast"], "10.0.0.255");
+    assert_eq!(records[1]["network"], "2001:db8::");
+}
+
+#[test]
+fn test_aggregate_mixed_families_flags() {
+    let output = subnetcalc(&[
+        "aggregate",
+        "--show-members",
+        "10.0.0.0/8",
+        "11.0.0.0/8",
+        "2001:db8::/32",
+        "2001:db8:1::/48",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Aggregated Network: 10.0.0.0/7\n  \
+         10.0.0.0/8\n  \
+         11.0.0.0/8\n\
+         combined 2 networks into 1 (/7), saving 1 table entry\n\
+         Aggregated Network: 2001:db8::/47\n  \
+         2001:db8::/32 (not contained)\n  \
+         2001:db8:1::/48\n\
+         combined 2 networks into 1 (/47), saving 1 table entry\n"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains(
+        "warning: 10.0.0.0/7 is shorter than the classful /8 and spans 2 class A blocks"
+    ));
+
+    let output = subnetcalc(&[
+        "aggregate",
+        "--quiet",
+        "10.0.0.0/8",
+        "11.0.0.0/8",
+        "2001:db8::/48",
+    ]);
+    assert_eq!(
+        stdout(&output),
+        "Aggregated Network: 10.0.0.0/7\nAggregated Network: 2001:db8::/48\n"
+    );
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("warning"));
+
+    let output = subnetcalc(&[
+        "aggregate",
+        "--min-prefix",
+        "8",
+        "10.0.0.0/24",
+        "2001:db8::/48",
+    ]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: Invalid format: --min-prefix only applies to IPv4 networks"));
+
+    for format in ["csv", "table"] {
+        let output = subnetcalc(&["--format", format, "aggregate", "2001:db8::/48"]);
+        assert!(!output.status.success());
+        assert!(stdout(&output).is_empty());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("no csv or table layout"));
+    }
+}
+
+#[test]
+fn test_aggregate_exact() {
+    let output = subnetcalc(&["aggregate", "--exact", "192.168.0.0/24", "192.168.255.0/24"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Aggregated Networks:\n  192.168.0.0/24\n  192.168.255.0/24\ncombined 2 networks into 2\n"
+    );
+
+    let output = subnetcalc(&[
+        "aggregate",
+        "--exact",
+        "--json",
+        "10.0.0.0/24",
+        "10.0.1.0/24",
+    ]);
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["prefix"], 23);
+
+    let output = subnetcalc(&["aggregate", "--exact", "--show-members", "10.0.0.0/24"]);
+    assert!(!output.status.success());
+
+    let output = subnetcalc(&[
+        "aggregate",
+        "--exact",
+        "10.0.0.0/24",
+        "2001:db8::/48",
+        "2001:db8:1::/48",
+        "2001:db8:3::/48",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Aggregated Networks:\n  \
+         10.0.0.0/24\n  \
+         2001:db8::/47\n  \
+         2001:db8:3::/48\n\
+         combined 4 networks into 3\n"
+    );
+
+    let output = subnetcalc(&["aggregate", "--exact", "--json", "2001:db8::/48"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(
+        json[0]["last_address"],
+        "2001:db8:0:ffff:ffff:ffff:ffff:ffff"
+    );
+}
+
+#[test]
+fn test_aggregate_verify() {
+    let output = subnetcalc(&[
+        "aggregate",
+        "--verify",
+        "--quiet",
+        "10.0.0.0/24",
+        "10.0.1.0/24",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Aggregated Network: 10.0.0.0/23\n\
+         verified: the aggregate covers exactly the input addresses\n"
+    );
+
+    let output = subnetcalc(&[
+        "aggregate",
+        "--verify",
+        "--quiet",
+        "192.168.0.0/24",
+        "192.168.3.0/24",
+    ]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Aggregated Network: 192.168.0.0/22\n\
+         verify failed: absorbed 512 extra addresses not in the inputs:\n  \
+         192.168.1.0/24 (192.168.1.0 - 192.168.1.255)\n  \
+         192.168.2.0/24 (192.168.2.0 - 192.168.2.255)\n"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: The aggregate would cover addresses outside the networks."));
+
+    // An input reaching past the aggregate is reported rather than failing the subtraction
+    let output = subnetcalc(&[
+        "aggregate",
+        "--verify",
+        "--quiet",
+        "192.168.0.0/16",
+        "192.168.1.0/24",
+    ]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Aggregated Network: 192.168.0.0/23\n\
+         verify failed: 1 network not covered by the aggregate:\n  \
+         192.168.0.0/16\n"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Error: A network is not contained in its parent network."));
+}
+
+#[test]
+fn test_aggregate_verify_ipv6_and_formats() {
+    let output = subnetcalc(&[
+        "aggregate",
+        "--verify",
+        "--quiet",
+        "10.0.0.0/24",
+        "10.0.1.0/24",
+        "2001:db8::/48",
+        "2001:db8:3::/48",
+    ]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Aggregated Network: 10.0.0.0/23\n\
+         Aggregated Network: 2001:db8::/46\n\
+         verify failed: absorbed 2^81 extra addresses not in the inputs:\n  \
+         2001:db8:1::/48 (2001:db8:1:: - 2001:db8:1:ffff:ffff:ffff:ffff:ffff)\n  \
+         2001:db8:2::/48 (2001:db8:2:: - 2001:db8:2:ffff:ffff:ffff:ffff:ffff)\n"
+    );
+
+    let output = subnetcalc(&["aggregate", "--verify", "2001:db8::/48", "2001:db8:1::/48"]);
+    assert!(output.status.success());
+    assert!(
+        stdout(&output).ends_with("verified: the aggregate covers exactly the input addresses\n")
+    );
+
+    let output = subnetcalc_with_env(
+        &["aggregate", "--verify", "10.0.0.0/24", "10.0.1.0/24"],
+        &[("SUBNETCALC_FORMAT", "json")],
+    );
+    assert!(!output.status.success());
+    assert!(stdout(&output).is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--verify only reports as text"));
+
+    let output = subnetcalc(&["--format", "csv", "aggregate", "--verify", "10.0.0.0/24"]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).is_empty());
+}
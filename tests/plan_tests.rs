@@ -0,0 +1,69 @@
+use std::net::Ipv4Addr;
+use subnetcalc::errors::NetworkError;
+use subnetcalc::plan::SubnetPlan;
+use subnetcalc::subnet::Network;
+
+#[test]
+fn test_builder_solve() {
+    let plan = SubnetPlan::builder()
+        .base_prefix(16)
+        .subnets(320)
+        .hosts(90)
+        .solve()
+        .unwrap();
+    assert_eq!(plan.prefix, 25);
+    assert_eq!(plan.netmask, Ipv4Addr::new(255, 255, 255, 128));
+    assert_eq!(plan.usable_hosts, 126);
+    assert_eq!(plan.subnets, 512);
+    assert_eq!(
+        Network::determine_subnet_mask(16, 320, 90).unwrap(),
+        plan.netmask
+    );
+}
+
+#[test]
+fn test_builder_missing_parameters() {
+    let result = SubnetPlan::builder().subnets(4).hosts(10).solve();
+    assert_eq!(result.unwrap_err(), NetworkError::InvalidPrefixLength);
+
+    let result = SubnetPlan::builder().base_prefix(24).hosts(10).solve();
+    assert_eq!(result.unwrap_err(), NetworkError::InvalidHostsOrSubnets);
+
+    let result = SubnetPlan::builder()
+        .base_prefix(24)
+        .subnets(0)
+        .hosts(10)
+        .solve();
+    assert_eq!(result.unwrap_err(), NetworkError::InvalidHostsOrSubnets);
+}
+
+#[test]
+fn test_builder_insufficient_bits() {
+    // 4 subnets of 100 hosts need 2 + 7 bits, a /24 only has 8
+    let result = SubnetPlan::builder()
+        .base_prefix(24)
+        .subnets(4)
+        .hosts(100)
+        .solve();
+    assert_eq!(result.unwrap_err(), NetworkError::InsufficientBits);
+
+    let result = SubnetPlan::builder()
+        .base_prefix(24)
+        .subnets(2)
+        .hosts(u32::MAX)
+        .solve();
+    assert_eq!(result.unwrap_err(), NetworkError::InsufficientBits);
+
+    // 90 subnets of 350 hosts need 7 + 9 bits, which a /16 has and a /24 doesn't
+    let plan = SubnetPlan::builder()
+        .base_prefix(16)
+        .subnets(90)
+        .hosts(350)
+        .solve()
+        .unwrap();
+    assert_eq!(plan.prefix, 23);
+    assert_eq!(
+        Network::determine_subnet_mask(24, 90, 350).unwrap_err(),
+        NetworkError::InsufficientBits
+    );
+}
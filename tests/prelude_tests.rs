@@ -0,0 +1,23 @@
+use std::net::Ipv4Addr;
+use std::str::FromStr;
+use subnetcalc::prelude::*;
+
+#[test]
+fn test_prelude_imports() {
+    let networks = [
+        Network::from_str("192.168.0.0/24").unwrap(),
+        Network::from_str("192.168.1.0/24").unwrap(),
+    ];
+    let aggregate = Network::aggregate_networks(&networks).unwrap();
+    assert_eq!(aggregate, Network::new(Ipv4Addr::new(192, 168, 0, 0), 23));
+
+    assert_eq!(
+        Network::determine_subnet_mask(16, 320, 90).unwrap(),
+        Ipv4Addr::new(255, 255, 255, 128)
+    );
+    assert_eq!(Network::mask_to_u32(24), 0xffff_ff00);
+    assert_eq!(Network::default_mask(Ipv4Addr::new(10, 0, 0, 0)), 8);
+
+    let error: NetworkError = Network::aggregate_networks(&[]).unwrap_err();
+    assert_eq!(error, NetworkError::EmptyNetworkList);
+}
@@ -0,0 +1,33 @@
+use proptest::prelude::*;
+use std::net::Ipv4Addr;
+use subnetcalc::subnet::Network;
+
+proptest! {
+    #[test]
+    fn prop_netmask_round_trip(prefix in 0u32..=32) {
+        let netmask = Network::new(Ipv4Addr::UNSPECIFIED, prefix).netmask_address();
+        prop_assert_eq!(Network::prefix_from_netmask(netmask).unwrap(), prefix);
+        prop_assert_eq!(u32::from(netmask), Network::mask_to_u32(prefix));
+    }
+
+    #[test]
+    fn prop_mask_to_u32_is_contiguous(prefix in 0u32..=32) {
+        let mask = Network::mask_to_u32(prefix);
+        prop_assert_eq!(mask.leading_ones(), prefix);
+        prop_assert_eq!(mask.count_ones(), prefix);
+    }
+
+    #[test]
+    fn prop_network_wildcard_broadcast(ip in any::<u32>(), prefix in 0u32..=32) {
+        let network = Network::new(Ipv4Addr::from(ip), prefix);
+        let network_u32 = u32::from(network.network_address());
+        let wildcard = u32::from(network.wildcard_address());
+        let broadcast = u32::from(network.broadcast_address());
+        prop_assert_eq!(network_u32 | wildcard, broadcast);
+        prop_assert_eq!(network_u32 & wildcard, 0);
+        prop_assert_eq!(
+            u64::from(broadcast - network_u32) + 1,
+            network.address_count()
+        );
+    }
+}
@@ -1,7 +1,8 @@
-use std::net::Ipv4Addr;
+use std::collections::HashSet;
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 use subnetcalc::errors::NetworkError;
-use subnetcalc::subnet::Network;
+use subnetcalc::subnet::{BitKind, HostCountPolicy, IpNetwork, Ipv6Network, Network, NetworkInfo};
 
 #[test]
 fn test_parse_subnet_valid() {
@@ -179,3 +180,1744 @@ fn determine_subnet_mask() {
     assert!(result.is_ok());
     assert_eq!(result.unwrap(), Ipv4Addr::new(255, 255, 255, 128));
 }
+
+#[test]
+fn test_from_str_trims_whitespace() {
+    let subnet = Network::from_str(" 192.168.1.0/24 ").unwrap();
+    assert_eq!(subnet, Network::new(Ipv4Addr::new(192, 168, 1, 0), 24));
+
+    let subnet = Network::from_str("\t10.0.0.0/8\t\n").unwrap();
+    assert_eq!(subnet, Network::new(Ipv4Addr::new(10, 0, 0, 0), 8));
+}
+
+#[test]
+fn test_from_str_spaced_slash() {
+    let subnet = Network::from_str("192.168.1.0 / 24").unwrap();
+    assert_eq!(subnet, Network::new(Ipv4Addr::new(192, 168, 1, 0), 24));
+
+    let subnet = Network::from_str("  192.168.1.0 /24 ").unwrap();
+    assert_eq!(subnet, Network::new(Ipv4Addr::new(192, 168, 1, 0), 24));
+}
+
+#[test]
+fn test_from_str_inner_whitespace_invalid() {
+    let result = Network::from_str("192.168. 1.0/24");
+    assert_eq!(result.unwrap_err(), NetworkError::InvalidIpFormat);
+
+    let result = Network::from_str("192.168.1.0/2 4");
+    assert_eq!(result.unwrap_err(), NetworkError::InvalidMaskFormat);
+}
+
+#[test]
+fn test_network_address() {
+    let subnet = Network::new(Ipv4Addr::new(192, 168, 1, 37), 24);
+    assert_eq!(subnet.network_address(), Ipv4Addr::new(192, 168, 1, 0));
+}
+
+#[test]
+fn test_hosts_iterator() {
+    let subnet = Network::new(Ipv4Addr::new(192, 168, 1, 0), 30);
+    let hosts: Vec<Ipv4Addr> = subnet.hosts().collect();
+    assert_eq!(
+        hosts,
+        vec![Ipv4Addr::new(192, 168, 1, 1), Ipv4Addr::new(192, 168, 1, 2)]
+    );
+
+    let subnet = Network::new(Ipv4Addr::new(10, 0, 0, 0), 8);
+    assert_eq!(subnet.hosts().next(), Some(Ipv4Addr::new(10, 0, 0, 1)));
+}
+
+#[test]
+fn test_host_arpa_names() {
+    let subnet = Network::new(Ipv4Addr::new(192, 168, 1, 0), 30);
+    let names: Vec<String> = subnet.host_arpa_names().collect();
+    assert_eq!(names.len(), 2);
+    assert_eq!(names.first().unwrap(), "1.1.168.192.in-addr.arpa");
+    assert_eq!(names.last().unwrap(), "2.1.168.192.in-addr.arpa");
+}
+
+#[test]
+fn test_contains_network() {
+    let parent = Network::new(Ipv4Addr::new(192, 168, 0, 0), 16);
+    assert!(parent.contains_network(&Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)));
+    assert!(parent.contains_network(&parent));
+    assert!(!parent.contains_network(&Network::new(Ipv4Addr::new(192, 0, 0, 0), 8)));
+    assert!(!parent.contains_network(&Network::new(Ipv4Addr::new(10, 0, 0, 0), 24)));
+}
+
+#[test]
+fn test_validate() {
+    assert!(Network::new(Ipv4Addr::new(10, 0, 0, 0), 8)
+        .validate()
+        .is_ok());
+    assert!(Network::new(Ipv4Addr::new(0, 0, 0, 0), 0)
+        .validate()
+        .is_ok());
+    assert!(Network::new(Ipv4Addr::new(10, 0, 0, 1), 32)
+        .validate()
+        .is_ok());
+
+    let result = Network::new(Ipv4Addr::new(10, 0, 0, 0), 40).validate();
+    assert_eq!(result.unwrap_err(), NetworkError::InvalidPrefixLength);
+}
+
+#[test]
+fn test_checked_accessors() {
+    let subnet = Network::new(Ipv4Addr::new(192, 168, 1, 37), 24);
+    assert_eq!(
+        subnet.checked_network(),
+        Some(Ipv4Addr::new(192, 168, 1, 0))
+    );
+    assert_eq!(
+        subnet.checked_broadcast(),
+        Some(Ipv4Addr::new(192, 168, 1, 255))
+    );
+    assert_eq!(subnet.checked_hosts(), Some(254));
+
+    let subnet = Network::new(Ipv4Addr::new(10, 1, 2, 3), 0);
+    assert_eq!(subnet.checked_network(), Some(Ipv4Addr::new(0, 0, 0, 0)));
+    assert_eq!(
+        subnet.checked_broadcast(),
+        Some(Ipv4Addr::new(255, 255, 255, 255))
+    );
+    assert_eq!(subnet.checked_hosts(), Some(4_294_967_294));
+
+    let subnet = Network::new(Ipv4Addr::new(10, 0, 0, 1), 32);
+    assert_eq!(subnet.checked_hosts(), Some(0));
+}
+
+#[test]
+fn test_checked_accessors_invalid_prefix() {
+    let subnet = Network::new(Ipv4Addr::new(10, 0, 0, 0), 40);
+    assert_eq!(subnet.checked_network(), None);
+    assert_eq!(subnet.checked_broadcast(), None);
+    assert_eq!(subnet.checked_hosts(), None);
+}
+
+#[test]
+fn test_summarize_networks() {
+    let networks = vec![
+        Network::new(Ipv4Addr::new(10, 0, 1, 0), 24),
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 24),
+        Network::new(Ipv4Addr::new(10, 0, 4, 0), 24),
+        Network::new(Ipv4Addr::new(10, 0, 4, 128), 25),
+    ];
+
+    let result = Network::summarize_networks(&networks);
+    assert_eq!(
+        result,
+        vec![
+            Network::new(Ipv4Addr::new(10, 0, 0, 0), 23),
+            Network::new(Ipv4Addr::new(10, 0, 4, 0), 24),
+        ]
+    );
+}
+
+#[test]
+fn test_summarize_networks_non_siblings() {
+    // Adjacent but not aligned on a common parent, so nothing merges
+    let networks = vec![
+        Network::new(Ipv4Addr::new(10, 0, 1, 0), 24),
+        Network::new(Ipv4Addr::new(10, 0, 2, 0), 24),
+    ];
+
+    let result = Network::summarize_networks(&networks);
+    assert_eq!(result, networks);
+}
+
+#[test]
+fn test_summarize_networks_nested() {
+    let networks = vec![
+        Network::new(Ipv4Addr::new(10, 1, 0, 0), 16),
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 8),
+        Network::new(Ipv4Addr::new(10, 2, 0, 0), 16),
+    ];
+    let result = Network::summarize_networks(&networks);
+    assert_eq!(result, vec![Network::new(Ipv4Addr::new(10, 0, 0, 0), 8)]);
+
+    // A network nested in a freshly merged parent is dropped as well
+    let networks = vec![
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 24),
+        Network::new(Ipv4Addr::new(10, 0, 1, 0), 24),
+        Network::new(Ipv4Addr::new(10, 0, 1, 128), 25),
+    ];
+    let result = Network::summarize_networks(&networks);
+    assert_eq!(result, vec![Network::new(Ipv4Addr::new(10, 0, 0, 0), 23)]);
+}
+
+#[test]
+fn test_network_info() {
+    let info = NetworkInfo::from(&Network::new(Ipv4Addr::new(192, 168, 1, 0), 26));
+    assert_eq!(info.network, Ipv4Addr::new(192, 168, 1, 0));
+    assert_eq!(info.prefix, 26);
+    assert_eq!(info.netmask, Ipv4Addr::new(255, 255, 255, 192));
+    assert_eq!(info.broadcast, Some(Ipv4Addr::new(192, 168, 1, 63)));
+    assert_eq!(info.available_hosts, 62);
+}
+
+#[test]
+fn test_overlaps() {
+    let a = Network::new(Ipv4Addr::new(10, 0, 0, 0), 16);
+    let b = Network::new(Ipv4Addr::new(10, 0, 1, 0), 24);
+    let c = Network::new(Ipv4Addr::new(10, 1, 0, 0), 24);
+    assert!(a.overlaps(&b));
+    assert!(b.overlaps(&a));
+    assert!(!a.overlaps(&c));
+}
+
+#[test]
+fn test_address_count() {
+    assert_eq!(
+        Network::new(Ipv4Addr::new(192, 168, 1, 0), 24).address_count(),
+        256
+    );
+    assert_eq!(
+        Network::new(Ipv4Addr::new(192, 168, 1, 1), 32).address_count(),
+        1
+    );
+    assert_eq!(
+        Network::new(Ipv4Addr::new(0, 0, 0, 0), 0).address_count(),
+        1 << 32
+    );
+}
+
+#[test]
+fn test_usable_hosts_classic() {
+    let policy = HostCountPolicy::Classic;
+    let ip = Ipv4Addr::new(10, 0, 0, 0);
+    assert_eq!(Network::new(ip, 30).usable_hosts_with(policy), 2);
+    assert_eq!(Network::new(ip, 31).usable_hosts_with(policy), 2);
+    assert_eq!(Network::new(ip, 32).usable_hosts_with(policy), 0);
+    assert_eq!(HostCountPolicy::default(), policy);
+}
+
+#[test]
+fn test_usable_hosts_rfc3021() {
+    let policy = HostCountPolicy::Rfc3021;
+    let ip = Ipv4Addr::new(10, 0, 0, 0);
+    assert_eq!(Network::new(ip, 30).usable_hosts_with(policy), 2);
+    assert_eq!(Network::new(ip, 31).usable_hosts_with(policy), 2);
+    assert_eq!(Network::new(ip, 32).usable_hosts_with(policy), 1);
+}
+
+#[test]
+fn test_usable_hosts_all_addresses() {
+    let policy = HostCountPolicy::AllAddresses;
+    let ip = Ipv4Addr::new(10, 0, 0, 0);
+    assert_eq!(Network::new(ip, 30).usable_hosts_with(policy), 4);
+    assert_eq!(Network::new(ip, 31).usable_hosts_with(policy), 2);
+    assert_eq!(Network::new(ip, 32).usable_hosts_with(policy), 1);
+}
+
+#[test]
+fn test_special_class_note() {
+    let subnet = Network::new(Ipv4Addr::new(127, 0, 0, 1), 8);
+    assert_eq!(subnet.ip_class(), 'A');
+    assert_eq!(subnet.special_class_note(), Some("loopback"));
+
+    let subnet = Network::new(Ipv4Addr::new(0, 0, 0, 1), 8);
+    assert_eq!(subnet.ip_class(), 'A');
+    assert_eq!(subnet.special_class_note(), Some("this-network"));
+
+    let subnet = Network::new(Ipv4Addr::new(10, 1, 2, 3), 8);
+    assert_eq!(subnet.special_class_note(), None);
+}
+
+#[test]
+fn test_size_ratio() {
+    let a = Network::new(Ipv4Addr::new(192, 168, 1, 0), 24);
+    let b = Network::new(Ipv4Addr::new(192, 168, 1, 1), 32);
+    assert_eq!(Network::size_ratio(&a, &b), 256.0);
+    assert_eq!(Network::size_ratio(&b, &a), 1.0 / 256.0);
+    assert_eq!(Network::size_ratio(&a, &a), 1.0);
+}
+
+#[test]
+fn test_prefix_delta() {
+    let a = Network::new(Ipv4Addr::new(192, 168, 1, 0), 24);
+    let b = Network::new(Ipv4Addr::new(192, 168, 1, 1), 32);
+    assert_eq!(Network::prefix_delta(&a, &b), 8);
+    assert_eq!(Network::prefix_delta(&b, &a), -8);
+    assert_eq!(Network::prefix_delta(&a, &a), 0);
+}
+
+#[test]
+fn test_mask_to_u32_edges() {
+    assert_eq!(Network::mask_to_u32(0), 0);
+    assert_eq!(Network::mask_to_u32(32), u32::MAX);
+}
+
+#[test]
+fn test_hosts_edges() {
+    let ip = Ipv4Addr::new(10, 0, 0, 0);
+    assert_eq!(Network::new(ip, 0).available_hosts(), 4_294_967_294);
+    assert_eq!(Network::new(ip, 31).available_hosts(), 2);
+    assert_eq!(Network::new(ip, 32).available_hosts(), 0);
+}
+
+#[test]
+fn test_from_network_broadcast() {
+    let result = Network::from_network_broadcast(
+        Ipv4Addr::new(192, 168, 1, 0),
+        Ipv4Addr::new(192, 168, 1, 255),
+    );
+    assert_eq!(
+        result.unwrap(),
+        Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)
+    );
+
+    let result =
+        Network::from_network_broadcast(Ipv4Addr::new(10, 0, 0, 64), Ipv4Addr::new(10, 0, 0, 127));
+    assert_eq!(
+        result.unwrap(),
+        Network::new(Ipv4Addr::new(10, 0, 0, 64), 26)
+    );
+
+    let result =
+        Network::from_network_broadcast(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 1));
+    assert_eq!(
+        result.unwrap(),
+        Network::new(Ipv4Addr::new(10, 0, 0, 1), 32)
+    );
+}
+
+#[test]
+fn test_from_network_broadcast_inconsistent() {
+    // Not the aligned network for a /24
+    let result = Network::from_network_broadcast(
+        Ipv4Addr::new(192, 168, 1, 5),
+        Ipv4Addr::new(192, 168, 1, 255),
+    );
+    assert_eq!(result.unwrap_err(), NetworkError::InconsistentBroadcast);
+
+    // Differing bits are not a contiguous host part
+    let result = Network::from_network_broadcast(
+        Ipv4Addr::new(192, 168, 0, 0),
+        Ipv4Addr::new(192, 168, 2, 255),
+    );
+    assert_eq!(result.unwrap_err(), NetworkError::InconsistentBroadcast);
+}
+
+#[test]
+fn test_network_hash_set() {
+    let mut seen = HashSet::new();
+    assert!(seen.insert(Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)));
+    assert!(seen.insert(Network::new(Ipv4Addr::new(192, 168, 1, 0), 25)));
+    assert!(!seen.insert(Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)));
+    // Hashing is address-exact, a host address is a different key
+    assert!(seen.insert(Network::new(Ipv4Addr::new(192, 168, 1, 1), 24)));
+    assert_eq!(seen.len(), 3);
+}
+
+#[test]
+fn test_point_to_point() {
+    let subnet = Network::new(Ipv4Addr::new(10, 0, 0, 0), 31);
+    assert!(subnet.is_point_to_point());
+    assert!(!Network::new(Ipv4Addr::new(10, 0, 0, 0), 30).is_point_to_point());
+
+    let hosts: Vec<Ipv4Addr> = subnet.hosts().collect();
+    assert_eq!(
+        hosts,
+        vec![Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 0, 1)]
+    );
+    assert_eq!(
+        subnet.usable_range(),
+        Ok((Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 0, 1)))
+    );
+    assert_eq!(subnet.available_hosts(), 2);
+    assert_eq!(subnet.checked_hosts(), Some(2));
+    let info = NetworkInfo::from(&subnet);
+    assert_eq!(info.broadcast, None);
+    assert_eq!(info.available_hosts, u64::from(subnet.available_hosts()));
+}
+
+#[test]
+fn test_usable_range() {
+    let subnet = Network::new(Ipv4Addr::new(192, 168, 1, 0), 24);
+    assert_eq!(
+        subnet.usable_range(),
+        Ok((
+            Ipv4Addr::new(192, 168, 1, 1),
+            Ipv4Addr::new(192, 168, 1, 254)
+        ))
+    );
+
+    let subnet = Network::new(Ipv4Addr::new(192, 168, 1, 1), 32);
+    assert_eq!(subnet.usable_range(), Err(NetworkError::NoUsableHosts));
+    assert_eq!(subnet.hosts().count(), 0);
+}
+
+#[test]
+fn test_split() {
+    let parent = Network::new(Ipv4Addr::new(192, 168, 1, 0), 24);
+    let result = parent.split(3).unwrap();
+    assert_eq!(
+        result,
+        vec![
+            Network::new(Ipv4Addr::new(192, 168, 1, 0), 26),
+            Network::new(Ipv4Addr::new(192, 168, 1, 64), 26),
+            Network::new(Ipv4Addr::new(192, 168, 1, 128), 26),
+            Network::new(Ipv4Addr::new(192, 168, 1, 192), 26),
+        ]
+    );
+    assert_eq!(parent.split(1).unwrap(), vec![parent]);
+}
+
+#[test]
+fn test_split_invalid() {
+    let parent = Network::new(Ipv4Addr::new(192, 168, 1, 0), 30);
+    assert_eq!(
+        parent.split(0).unwrap_err(),
+        NetworkError::InvalidHostsOrSubnets
+    );
+    assert_eq!(parent.split(8).unwrap_err(), NetworkError::InsufficientBits);
+}
+
+#[test]
+fn test_split_limit() {
+    let parent = Network::new(Ipv4Addr::new(0, 0, 0, 0), 0);
+    assert_eq!(
+        parent.split(Network::SPLIT_MAX_SUBNETS).unwrap().len(),
+        Network::SPLIT_MAX_SUBNETS as usize
+    );
+    assert_eq!(
+        parent.split(Network::SPLIT_MAX_SUBNETS + 1).unwrap_err(),
+        NetworkError::TooManyAddresses
+    );
+    assert_eq!(
+        parent.split(2_147_483_648).unwrap_err(),
+        NetworkError::TooManyAddresses
+    );
+}
+
+#[test]
+fn test_render_division() {
+    let parent = Network::new(Ipv4Addr::new(192, 168, 1, 0), 24);
+    let children = parent.split(4).unwrap();
+
+    let rendered = Network::render_division(&parent, &children);
+    for child in &children {
+        assert!(rendered.contains(&child.to_string()));
+    }
+    let bar = rendered.lines().nth(1).unwrap();
+    assert_eq!(bar.matches('|').count() - 1, 4);
+}
+
+#[test]
+fn test_common_supernet() {
+    let networks = vec![
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 24),
+        Network::new(Ipv4Addr::new(10, 0, 3, 0), 24),
+    ];
+    let result = Network::common_supernet(&networks).unwrap();
+    assert_eq!(result, Network::new(Ipv4Addr::new(10, 0, 0, 0), 22));
+
+    // Unlike aggregate_networks, the shorter input prefix is fully covered
+    let networks = vec![
+        Network::new(Ipv4Addr::new(192, 168, 0, 0), 16),
+        Network::new(Ipv4Addr::new(192, 168, 1, 0), 24),
+    ];
+    let result = Network::common_supernet(&networks).unwrap();
+    assert_eq!(result, Network::new(Ipv4Addr::new(192, 168, 0, 0), 16));
+}
+
+#[test]
+fn test_common_supernet_empty() {
+    let result = Network::common_supernet(&[]);
+    assert_eq!(result.unwrap_err(), NetworkError::EmptyNetworkList);
+}
+
+#[test]
+fn test_crosses_octet_boundary() {
+    assert!(!Network::new(Ipv4Addr::new(10, 0, 0, 0), 8).crosses_octet_boundary());
+    assert!(!Network::new(Ipv4Addr::new(192, 168, 1, 0), 24).crosses_octet_boundary());
+    assert!(Network::new(Ipv4Addr::new(192, 168, 1, 0), 25).crosses_octet_boundary());
+    assert!(Network::new(Ipv4Addr::new(192, 168, 1, 32), 27).crosses_octet_boundary());
+}
+
+#[test]
+fn test_bitstring_round_trip() {
+    let subnet = Network::new(Ipv4Addr::new(192, 168, 1, 0), 24);
+    let bits = subnet.bitstring();
+    assert_eq!(bits, "11000000101010000000000100000000");
+    assert_eq!(Network::from_bitstring(&bits, 24).unwrap(), subnet);
+}
+
+#[test]
+fn test_from_bitstring_invalid() {
+    let result = Network::from_bitstring("1100000010101000000000010000000", 24);
+    assert_eq!(result.unwrap_err(), NetworkError::InvalidIpFormat);
+
+    let result = Network::from_bitstring("1100000010101000000000010000000x", 24);
+    assert_eq!(result.unwrap_err(), NetworkError::InvalidIpFormat);
+
+    let result = Network::from_bitstring("11000000101010000000000100000000", 33);
+    assert_eq!(result.unwrap_err(), NetworkError::InvalidPrefixLength);
+}
+
+#[test]
+fn test_aggregate_with_floor() {
+    let networks = vec![
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 24),
+        Network::new(Ipv4Addr::new(10, 0, 1, 0), 24),
+    ];
+    let result = Network::aggregate_networks_with_floor(&networks, 8).unwrap();
+    assert_eq!(result, Network::new(Ipv4Addr::new(10, 0, 0, 0), 23));
+}
+
+#[test]
+fn test_aggregate_with_floor_rejected() {
+    let networks = vec![
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 8),
+        Network::new(Ipv4Addr::new(192, 168, 0, 0), 16),
+    ];
+    let result = Network::aggregate_networks_with_floor(&networks, 8);
+    assert_eq!(result.unwrap_err(), NetworkError::BelowMinimumPrefix);
+}
+
+#[test]
+fn test_is_aligned() {
+    assert!(Network::new(Ipv4Addr::new(192, 168, 1, 0), 24).is_aligned());
+    assert!(Network::new(Ipv4Addr::new(192, 168, 1, 37), 32).is_aligned());
+    assert!(!Network::new(Ipv4Addr::new(192, 168, 1, 37), 24).is_aligned());
+}
+
+#[test]
+fn test_diff() {
+    let old = vec![
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 24),
+        Network::new(Ipv4Addr::new(10, 0, 1, 0), 24),
+    ];
+    let new = vec![
+        Network::new(Ipv4Addr::new(10, 0, 1, 0), 24),
+        Network::new(Ipv4Addr::new(10, 0, 2, 0), 24),
+    ];
+
+    let (added, removed) = Network::diff(&old, &new);
+    assert_eq!(added, vec![Network::new(Ipv4Addr::new(10, 0, 2, 0), 24)]);
+    assert_eq!(removed, vec![Network::new(Ipv4Addr::new(10, 0, 0, 0), 24)]);
+}
+
+#[test]
+fn test_aggregate_contiguous() {
+    let networks = vec![
+        Network::new(Ipv4Addr::new(192, 168, 1, 128), 26),
+        Network::new(Ipv4Addr::new(192, 168, 1, 0), 26),
+        Network::new(Ipv4Addr::new(192, 168, 1, 192), 26),
+        Network::new(Ipv4Addr::new(192, 168, 1, 64), 26),
+    ];
+    let result = Network::aggregate_contiguous(&networks).unwrap();
+    assert_eq!(result, Network::new(Ipv4Addr::new(192, 168, 1, 0), 24));
+}
+
+#[test]
+fn test_aggregate_contiguous_not_filling() {
+    let networks = vec![
+        Network::new(Ipv4Addr::new(192, 168, 1, 0), 26),
+        Network::new(Ipv4Addr::new(192, 168, 1, 64), 26),
+        Network::new(Ipv4Addr::new(192, 168, 1, 128), 26),
+    ];
+    let result = Network::aggregate_contiguous(&networks);
+    assert_eq!(result.unwrap_err(), NetworkError::NotContiguous);
+}
+
+#[test]
+fn test_aggregate_contiguous_gap_and_empty() {
+    let networks = vec![
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 24),
+        Network::new(Ipv4Addr::new(10, 0, 2, 0), 24),
+    ];
+    let result = Network::aggregate_contiguous(&networks);
+    assert_eq!(result.unwrap_err(), NetworkError::NotContiguous);
+
+    let result = Network::aggregate_contiguous(&[]);
+    assert_eq!(result.unwrap_err(), NetworkError::EmptyNetworkList);
+}
+
+#[test]
+fn test_split_off_first() {
+    let parent = Network::new(Ipv4Addr::new(192, 168, 1, 0), 24);
+    let (first, remainder) = parent.split_off_first(26).unwrap();
+    assert_eq!(first, Network::new(Ipv4Addr::new(192, 168, 1, 0), 26));
+    assert_eq!(
+        remainder,
+        vec![
+            Network::new(Ipv4Addr::new(192, 168, 1, 64), 26),
+            Network::new(Ipv4Addr::new(192, 168, 1, 128), 25),
+        ]
+    );
+
+    let (first, remainder) = parent.split_off_first(24).unwrap();
+    assert_eq!(first, parent);
+    assert!(remainder.is_empty());
+}
+
+#[test]
+fn test_split_off_first_invalid() {
+    let parent = Network::new(Ipv4Addr::new(192, 168, 1, 0), 24);
+    assert_eq!(
+        parent.split_off_first(16).unwrap_err(),
+        NetworkError::InsufficientBits
+    );
+    assert_eq!(
+        parent.split_off_first(33).unwrap_err(),
+        NetworkError::InvalidPrefixLength
+    );
+}
+
+#[test]
+fn test_bit_kinds() {
+    let kinds = Network::new(Ipv4Addr::new(10, 16, 0, 0), 20).bit_kinds();
+    assert!(kinds[..20].iter().all(|&kind| kind == BitKind::Network));
+    assert!(kinds[20..].iter().all(|&kind| kind == BitKind::Host));
+
+    let kinds = Network::new(Ipv4Addr::new(0, 0, 0, 0), 0).bit_kinds();
+    assert!(kinds.iter().all(|&kind| kind == BitKind::Host));
+}
+
+#[test]
+fn test_utilization() {
+    let parent = Network::new(Ipv4Addr::new(192, 168, 1, 0), 24);
+    let allocated = vec![
+        Network::new(Ipv4Addr::new(192, 168, 1, 0), 26),
+        Network::new(Ipv4Addr::new(192, 168, 1, 128), 26),
+    ];
+    assert_eq!(Network::utilization(&parent, &allocated).unwrap(), 0.5);
+    assert_eq!(Network::utilization(&parent, &[]).unwrap(), 0.0);
+}
+
+#[test]
+fn test_utilization_invalid() {
+    let parent = Network::new(Ipv4Addr::new(192, 168, 1, 0), 24);
+
+    let outside = vec![Network::new(Ipv4Addr::new(192, 168, 2, 0), 26)];
+    let result = Network::utilization(&parent, &outside);
+    assert_eq!(result.unwrap_err(), NetworkError::NotContained);
+
+    let overlapping = vec![
+        Network::new(Ipv4Addr::new(192, 168, 1, 0), 25),
+        Network::new(Ipv4Addr::new(192, 168, 1, 64), 26),
+    ];
+    let result = Network::utilization(&parent, &overlapping);
+    assert_eq!(result.unwrap_err(), NetworkError::OverlappingNetworks);
+}
+
+#[test]
+fn test_from_str_dotted_mask() {
+    let subnet = Network::from_str("192.168.1.0/255.255.255.192").unwrap();
+    assert_eq!(subnet, Network::new(Ipv4Addr::new(192, 168, 1, 0), 26));
+
+    let result = Network::from_str("192.168.1.0/255.0.255.0");
+    assert_eq!(result.unwrap_err(), NetworkError::NonContiguousMask);
+}
+
+#[test]
+fn test_from_str_diagnostics() {
+    let result = Network::from_str("300.1.1.1/24");
+    assert_eq!(
+        result.unwrap_err(),
+        NetworkError::InvalidOctet("300".to_string())
+    );
+
+    let result = Network::from_str("10.0.0.0/33");
+    assert_eq!(result.unwrap_err(), NetworkError::InvalidPrefixLength);
+
+    let result = Network::from_str("10.0.0/24");
+    assert_eq!(result.unwrap_err(), NetworkError::InvalidIpFormat);
+}
+
+#[test]
+fn test_prefix_from_netmask() {
+    assert_eq!(
+        Network::prefix_from_netmask(Ipv4Addr::new(255, 255, 255, 0)),
+        Ok(24)
+    );
+    assert_eq!(
+        Network::prefix_from_netmask(Ipv4Addr::new(0, 0, 0, 0)),
+        Ok(0)
+    );
+    assert_eq!(
+        Network::prefix_from_netmask(Ipv4Addr::new(255, 255, 255, 255)),
+        Ok(32)
+    );
+    assert_eq!(
+        Network::prefix_from_netmask(Ipv4Addr::new(255, 255, 0, 255)),
+        Err(NetworkError::NonContiguousMask)
+    );
+}
+
+#[test]
+fn test_from_str_leading_zero_octet() {
+    let result = Network::from_str("010.0.0.0/8");
+    assert_eq!(
+        result.unwrap_err(),
+        NetworkError::LeadingZeroOctet("010".to_string())
+    );
+
+    let result = Network::from_str("192.168.001.0/24");
+    assert_eq!(
+        result.unwrap_err(),
+        NetworkError::LeadingZeroOctet("001".to_string())
+    );
+
+    // A single zero is fine
+    let subnet = Network::from_str("10.0.0.0/8").unwrap();
+    assert_eq!(subnet, Network::new(Ipv4Addr::new(10, 0, 0, 0), 8));
+}
+
+#[test]
+fn test_expand() {
+    let subnet = Network::new(Ipv4Addr::new(192, 168, 1, 0), 30);
+    assert_eq!(
+        subnet.expand().unwrap(),
+        vec!["192.168.1.0", "192.168.1.1", "192.168.1.2", "192.168.1.3"]
+    );
+}
+
+#[test]
+fn test_expand_too_many_addresses() {
+    let subnet = Network::new(Ipv4Addr::new(10, 0, 0, 0), 8);
+    assert_eq!(subnet.expand().unwrap_err(), NetworkError::TooManyAddresses);
+
+    let subnet = Network::new(Ipv4Addr::new(10, 0, 0, 0), 16);
+    assert_eq!(subnet.expand().unwrap().len(), 65_536);
+}
+
+#[test]
+fn test_to_ipv4_mapped_ipv6() {
+    let network = Network::from_str("192.168.1.0/24").unwrap();
+    let mapped = network.to_ipv4_mapped_ipv6();
+    assert_eq!(
+        mapped,
+        Ipv6Network::new("::ffff:192.168.1.0".parse().unwrap(), 120)
+    );
+    assert_eq!(mapped.to_string(), "::ffff:192.168.1.0/120");
+}
+
+#[test]
+fn test_summary_line() {
+    let network = Network::from_str("192.168.1.70/26").unwrap();
+    assert_eq!(
+        network.summary_line(),
+        "192.168.1.64/26 (255.255.255.192, 62 hosts, class C)"
+    );
+}
+
+#[test]
+fn test_try_aggregate_pair() {
+    let a = Network::from_str("10.0.1.0/24").unwrap();
+    let b = Network::from_str("10.0.0.0/24").unwrap();
+    assert_eq!(
+        Network::try_aggregate_pair(&a, &b).unwrap(),
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 23)
+    );
+
+    // Adjacent but with different parents
+    let a = Network::from_str("10.0.1.0/24").unwrap();
+    let b = Network::from_str("10.0.2.0/24").unwrap();
+    assert_eq!(
+        Network::try_aggregate_pair(&a, &b).unwrap_err(),
+        NetworkError::NotContiguous
+    );
+
+    // Different sizes never merge into a single parent without waste
+    let a = Network::from_str("10.0.0.0/24").unwrap();
+    let b = Network::from_str("10.0.1.0/25").unwrap();
+    assert_eq!(
+        Network::try_aggregate_pair(&a, &b).unwrap_err(),
+        NetworkError::NotContiguous
+    );
+}
+
+#[test]
+fn test_contains_ips() {
+    let network = Network::from_str("192.168.1.16/28").unwrap();
+    assert!(network.contains(Ipv4Addr::new(192, 168, 1, 31)));
+    assert!(!network.contains(Ipv4Addr::new(192, 168, 1, 32)));
+
+    let ips = [
+        Ipv4Addr::new(192, 168, 1, 15),
+        Ipv4Addr::new(192, 168, 1, 16),
+        Ipv4Addr::new(192, 168, 1, 20),
+        Ipv4Addr::new(192, 168, 1, 31),
+        Ipv4Addr::new(10, 0, 0, 1),
+    ];
+    assert_eq!(network.count_contained(ips), 3);
+    assert!(network.contains_any(ips));
+    assert!(!network.contains_any([Ipv4Addr::new(10, 0, 0, 1)]));
+}
+
+#[test]
+fn test_shrink_to_fit() {
+    let network = Network::from_str("192.168.1.0/24").unwrap();
+    assert_eq!(
+        network.shrink_to_fit(50).unwrap(),
+        Network::new(Ipv4Addr::new(192, 168, 1, 0), 26)
+    );
+    // 62 hosts fill a /26 exactly, 254 the whole /24
+    assert_eq!(network.shrink_to_fit(62).unwrap().prefix, 26);
+    assert_eq!(network.shrink_to_fit(254).unwrap().prefix, 24);
+
+    assert_eq!(
+        network.shrink_to_fit(255).unwrap_err(),
+        NetworkError::InsufficientBits
+    );
+    assert_eq!(
+        network.shrink_to_fit(u32::MAX).unwrap_err(),
+        NetworkError::InsufficientBits
+    );
+}
+
+#[test]
+fn test_ancestors() {
+    let network = Network::from_str("10.1.2.0/24").unwrap();
+    let ancestors: Vec<Network> = network.ancestors().collect();
+    assert_eq!(ancestors.len(), 24);
+    assert_eq!(ancestors[0], Network::new(Ipv4Addr::new(10, 1, 2, 0), 23));
+    assert_eq!(ancestors[7], Network::new(Ipv4Addr::new(10, 1, 0, 0), 16));
+    assert_eq!(ancestors[8], Network::new(Ipv4Addr::new(10, 0, 0, 0), 15));
+    assert_eq!(
+        ancestors.last(),
+        Some(&Network::new(Ipv4Addr::UNSPECIFIED, 0))
+    );
+}
+
+#[test]
+fn test_is_subnet_of() {
+    let parent = Network::from_str("10.0.0.0/8").unwrap();
+    let child = Network::from_str("10.1.0.0/16").unwrap();
+    assert!(child.is_subnet_of(&parent));
+    assert!(parent.is_supernet_of(&child));
+    assert!(!parent.is_subnet_of(&child));
+    assert!(!child.is_supernet_of(&parent));
+
+    // Equal ranges contain each other but are neither strict subnet nor supernet
+    let same = Network::from_str("10.0.0.5/8").unwrap();
+    assert!(parent.contains_network(&same));
+    assert!(!parent.is_subnet_of(&same));
+    assert!(!parent.is_supernet_of(&same));
+
+    let other = Network::from_str("11.0.0.0/16").unwrap();
+    assert!(!other.is_subnet_of(&parent));
+}
+
+#[test]
+fn test_nth_host() {
+    let network = Network::from_str("192.168.1.0/24").unwrap();
+    assert_eq!(network.nth_host(0), Some(Ipv4Addr::new(192, 168, 1, 1)));
+    assert_eq!(network.nth_host(253), Some(Ipv4Addr::new(192, 168, 1, 254)));
+    assert_eq!(network.nth_host(254), None);
+    assert_eq!(network.nth_host(u32::MAX), None);
+
+    let point_to_point = Network::from_str("10.0.0.0/31").unwrap();
+    assert_eq!(point_to_point.nth_host(1), Some(Ipv4Addr::new(10, 0, 0, 1)));
+
+    let host = Network::from_str("10.0.0.1/32").unwrap();
+    assert_eq!(host.nth_host(0), None);
+}
+
+#[test]
+fn test_addr_parse_error_message() {
+    let error = Network::from_str("10.0.0.x/24").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid IP address format, invalid IPv4 address syntax."
+    );
+
+    let error = NetworkError::from("not an address".parse::<Ipv4Addr>().unwrap_err());
+    assert!(matches!(error, NetworkError::AddrParse(_)));
+    assert_eq!(error, NetworkError::InvalidIpFormat);
+    assert_eq!(error, NetworkError::AddrParse("another reason".to_string()));
+    assert_ne!(
+        NetworkError::InvalidOctet("300".to_string()),
+        NetworkError::InvalidOctet("256".to_string())
+    );
+
+    let seen: HashSet<NetworkError> = [error, NetworkError::InvalidIpFormat].into_iter().collect();
+    assert_eq!(seen.len(), 1);
+}
+
+#[test]
+fn test_new_normalized() {
+    let network = Network::new_normalized(Ipv4Addr::new(192, 168, 1, 37), 24).unwrap();
+    assert_eq!(network.ip, Ipv4Addr::new(192, 168, 1, 0));
+    assert_eq!(network.prefix, 24);
+
+    let result = Network::new_normalized(Ipv4Addr::new(192, 168, 1, 37), 33);
+    assert_eq!(result.unwrap_err(), NetworkError::InvalidPrefixLength);
+}
+
+#[test]
+fn test_aggregate_normalizes_inputs() {
+    let networks = vec![
+        Network::new(Ipv4Addr::new(192, 168, 1, 37), 24),
+        Network::new(Ipv4Addr::new(192, 168, 1, 200), 24),
+    ];
+    assert_eq!(
+        Network::aggregate_networks(&networks).unwrap(),
+        Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)
+    );
+
+    let single = [Network::new(Ipv4Addr::new(10, 1, 2, 3), 8)];
+    assert_eq!(
+        Network::aggregate_networks(&single).unwrap(),
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 8)
+    );
+}
+
+#[test]
+fn test_subtract_many() {
+    let parent = Network::from_str("192.168.1.0/24").unwrap();
+    let holes = [
+        Network::from_str("192.168.1.0/26").unwrap(),
+        Network::from_str("192.168.1.128/26").unwrap(),
+    ];
+    assert_eq!(
+        Network::subtract_many(&parent, &holes).unwrap(),
+        vec![
+            Network::new(Ipv4Addr::new(192, 168, 1, 64), 26),
+            Network::new(Ipv4Addr::new(192, 168, 1, 192), 26),
+        ]
+    );
+
+    let hole = Network::from_str("192.168.1.0/26").unwrap();
+    assert_eq!(
+        Network::subtract(&parent, &hole).unwrap(),
+        vec![
+            Network::new(Ipv4Addr::new(192, 168, 1, 64), 26),
+            Network::new(Ipv4Addr::new(192, 168, 1, 128), 25),
+        ]
+    );
+    assert_eq!(Network::subtract(&parent, &parent).unwrap(), vec![]);
+}
+
+#[test]
+fn test_subtract_many_invalid_holes() {
+    let parent = Network::from_str("192.168.1.0/24").unwrap();
+    let outside = [Network::from_str("192.168.2.0/26").unwrap()];
+    assert_eq!(
+        Network::subtract_many(&parent, &outside).unwrap_err(),
+        NetworkError::NotContained
+    );
+
+    let overlapping = [
+        Network::from_str("192.168.1.0/25").unwrap(),
+        Network::from_str("192.168.1.64/26").unwrap(),
+    ];
+    assert_eq!(
+        Network::subtract_many(&parent, &overlapping).unwrap_err(),
+        NetworkError::OverlappingNetworks
+    );
+}
+
+#[test]
+fn test_aggregate_grouped_ordering() {
+    let networks = vec![
+        Network::from_str("10.2.1.0/24").unwrap(),
+        Network::from_str("10.1.0.0/24").unwrap(),
+        Network::from_str("10.2.0.0/24").unwrap(),
+        Network::from_str("10.1.1.0/24").unwrap(),
+        Network::from_str("10.3.0.0/24").unwrap(),
+    ];
+    let mut shuffled = networks.clone();
+    shuffled.reverse();
+    shuffled.swap(0, 3);
+
+    let groups = Network::aggregate_grouped(&networks, 16).unwrap();
+    assert_eq!(groups, Network::aggregate_grouped(&shuffled, 16).unwrap());
+
+    let keys: Vec<String> = groups.keys().map(|key| key.to_string()).collect();
+    assert_eq!(keys, vec!["10.1.0.0/16", "10.2.0.0/16", "10.3.0.0/16"]);
+    assert_eq!(
+        Network::flatten_groups(&groups),
+        vec![
+            Network::new(Ipv4Addr::new(10, 1, 0, 0), 23),
+            Network::new(Ipv4Addr::new(10, 2, 0, 0), 23),
+            Network::new(Ipv4Addr::new(10, 3, 0, 0), 24),
+        ]
+    );
+}
+
+#[test]
+fn test_prefix_for_hosts() {
+    assert_eq!(Network::prefix_for_hosts(50).unwrap(), 26);
+    assert_eq!(Network::prefix_for_hosts(254).unwrap(), 24);
+    assert_eq!(Network::prefix_for_hosts(255).unwrap(), 23);
+    assert_eq!(Network::prefix_for_hosts(2).unwrap(), 30);
+    assert_eq!(Network::prefix_for_hosts(1).unwrap(), 30);
+    assert_eq!(Network::prefix_for_hosts(1 << 31).unwrap(), 0);
+    assert_eq!(Network::prefix_for_hosts(u32::MAX - 1).unwrap(), 0);
+
+    assert_eq!(
+        Network::prefix_for_hosts(0).unwrap_err(),
+        NetworkError::InvalidHostsOrSubnets
+    );
+    assert_eq!(
+        Network::prefix_for_hosts(u32::MAX).unwrap_err(),
+        NetworkError::InsufficientBits
+    );
+}
+
+#[test]
+fn test_render_html() {
+    let parent = Network::from_str("192.168.1.0/24").unwrap();
+    let children = [
+        Network::from_str("192.168.1.0/25").unwrap(),
+        Network::from_str("192.168.1.128/26").unwrap(),
+        Network::from_str("192.168.1.192/27").unwrap(),
+    ];
+    let html = Network::render_html(&parent, &children);
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<table>"));
+    assert!(html.contains("<svg"));
+    for child in &children {
+        assert!(html.contains(&format!("<td>{}</td>", child)));
+    }
+    assert!(html.contains("<rect x=\"320\" y=\"0\" width=\"160\""));
+}
+
+#[test]
+fn test_is_well_formed() {
+    assert!(Network::new(Ipv4Addr::new(192, 168, 1, 0), 24).is_well_formed());
+    assert!(Network::new(Ipv4Addr::new(192, 168, 1, 7), 32).is_well_formed());
+    assert!(Network::new(Ipv4Addr::UNSPECIFIED, 0).is_well_formed());
+
+    assert!(!Network::new(Ipv4Addr::new(192, 168, 1, 0), 33).is_well_formed());
+    assert!(!Network::new(Ipv4Addr::new(192, 168, 1, 37), 24).is_well_formed());
+}
+
+#[test]
+fn test_ip_network_from_str() {
+    let v4 = IpNetwork::from_str("192.168.1.37/24").unwrap();
+    assert_eq!(
+        v4,
+        IpNetwork::V4(Network::new(Ipv4Addr::new(192, 168, 1, 37), 24))
+    );
+    assert_eq!(v4.prefix(), 24);
+    assert_eq!(v4.network_address().to_string(), "192.168.1.0");
+    assert_eq!(
+        v4.broadcast_address().map(|ip| ip.to_string()),
+        Some("192.168.1.255".to_string())
+    );
+    assert!(v4.contains("192.168.1.200".parse().unwrap()));
+    assert!(!v4.contains("2001:db8::1".parse().unwrap()));
+
+    let v6 = IpNetwork::from_str("2001:db8:1::5/48").unwrap();
+    assert!(matches!(v6, IpNetwork::V6(_)));
+    assert_eq!(v6.prefix(), 48);
+    assert_eq!(v6.network_address().to_string(), "2001:db8:1::");
+    assert_eq!(v6.broadcast_address(), None);
+    assert!(v6.contains("2001:db8:1:ffff::1".parse().unwrap()));
+    assert!(!v6.contains("2001:db8:2::1".parse().unwrap()));
+    assert_eq!(v6.to_string(), "2001:db8:1::5/48");
+
+    assert_eq!(
+        IpNetwork::from_str("2001:db8::/129").unwrap_err(),
+        NetworkError::InvalidIpv6PrefixLength
+    );
+}
+
+#[test]
+fn test_split_for_two() {
+    let network = Network::from_str("192.168.1.0/24").unwrap();
+    assert_eq!(
+        network.split_for_two(100, 20).unwrap(),
+        (
+            Network::new(Ipv4Addr::new(192, 168, 1, 0), 25),
+            Network::new(Ipv4Addr::new(192, 168, 1, 128), 27),
+        )
+    );
+    assert_eq!(
+        network.split_for_two(20, 100).unwrap(),
+        (
+            Network::new(Ipv4Addr::new(192, 168, 1, 0), 27),
+            Network::new(Ipv4Addr::new(192, 168, 1, 128), 25),
+        )
+    );
+    assert_eq!(
+        network.split_for_two(200, 100).unwrap_err(),
+        NetworkError::InsufficientBits
+    );
+}
+
+#[test]
+fn test_parse_with_default_prefix() {
+    let network = Network::parse_with_default("10.0.0.5", Some(32)).unwrap();
+    assert_eq!(network, Network::new(Ipv4Addr::new(10, 0, 0, 5), 32));
+
+    // An explicit prefix always wins, no override keeps the classful default
+    let network = Network::parse_with_default("10.0.0.5/24", Some(32)).unwrap();
+    assert_eq!(network.prefix, 24);
+    let network = Network::parse_with_default("10.0.0.5", None).unwrap();
+    assert_eq!(network.prefix, 8);
+
+    let result = Network::parse_with_default("10.0.0.5", Some(33));
+    assert_eq!(result.unwrap_err(), NetworkError::InvalidPrefixLength);
+}
+
+#[test]
+fn test_coalesce() {
+    let mut networks: Vec<Network> = [
+        "10.0.1.0/24",
+        "10.0.0.0/24",
+        "10.0.1.0/24",
+        "10.0.0.128/25",
+        "10.0.3.7/24",
+        "10.0.2.0/24",
+        "172.16.0.0/16",
+    ]
+    .iter()
+    .map(|s| Network::from_str(s).unwrap())
+    .collect();
+
+    Network::coalesce(&mut networks);
+    assert_eq!(
+        networks,
+        vec![
+            Network::new(Ipv4Addr::new(10, 0, 0, 0), 22),
+            Network::new(Ipv4Addr::new(172, 16, 0, 0), 16),
+        ]
+    );
+
+    let once = networks.clone();
+    Network::coalesce(&mut networks);
+    assert_eq!(networks, once);
+}
+
+#[test]
+fn test_first_free() {
+    let parent = Network::from_str("10.0.0.0/24").unwrap();
+    let used = [Network::from_str("10.0.0.0/26").unwrap()];
+    assert_eq!(
+        Network::first_free(&parent, &used, 26).unwrap(),
+        Network::new(Ipv4Addr::new(10, 0, 0, 64), 26)
+    );
+
+    // A /25 has to start on a /25 boundary, skipping the rest of the first half
+    assert_eq!(
+        Network::first_free(&parent, &used, 25).unwrap(),
+        Network::new(Ipv4Addr::new(10, 0, 0, 128), 25)
+    );
+
+    let used = [
+        Network::from_str("10.0.0.0/25").unwrap(),
+        Network::from_str("10.0.0.128/25").unwrap(),
+    ];
+    assert_eq!(
+        Network::first_free(&parent, &used, 30).unwrap_err(),
+        NetworkError::NoFreeBlock
+    );
+}
+
+#[test]
+fn test_first_free_ignores_networks_outside_parent() {
+    let parent = Network::from_str("10.0.0.0/24").unwrap();
+    let used = [
+        Network::from_str("192.168.0.0/24").unwrap(),
+        Network::from_str("10.0.0.0/26").unwrap(),
+    ];
+    assert_eq!(
+        Network::first_free(&parent, &used, 26).unwrap(),
+        Network::new(Ipv4Addr::new(10, 0, 0, 64), 26)
+    );
+
+    // A used network covering the whole parent leaves nothing behind
+    let used = [Network::from_str("10.0.0.0/8").unwrap()];
+    assert_eq!(
+        Network::first_free(&parent, &used, 26).unwrap_err(),
+        NetworkError::NoFreeBlock
+    );
+}
+
+#[test]
+fn test_host_networks_at_address_space_edges() {
+    for ip in [Ipv4Addr::new(255, 255, 255, 255), Ipv4Addr::new(0, 0, 0, 0)] {
+        let network = Network::from_str(&format!("{}/32", ip)).unwrap();
+        assert_eq!(network, Network::new(ip, 32));
+        assert_eq!(network.network_address(), ip);
+        assert_eq!(network.broadcast_address(), ip);
+        assert_eq!(network.checked_network(), Some(ip));
+        assert_eq!(network.checked_broadcast(), Some(ip));
+        assert_eq!(network.available_hosts(), 0);
+        assert_eq!(network.checked_hosts(), Some(0));
+        assert_eq!(network.address_count(), 1);
+
+        let info = NetworkInfo::from(&network);
+        assert_eq!(info.network, ip);
+        assert_eq!(info.broadcast, Some(ip));
+        assert_eq!(info.netmask, Ipv4Addr::new(255, 255, 255, 255));
+        assert_eq!(info.wildcard, Ipv4Addr::new(0, 0, 0, 0));
+        assert_eq!(info.available_hosts, 0);
+    }
+}
+
+#[test]
+fn test_coverage_bitmap() {
+    let parent = Network::from_str("10.0.0.0/29").unwrap();
+    let allocated = [Network::from_str("10.0.0.4/30").unwrap()];
+    assert_eq!(
+        Network::coverage_bitmap(&parent, &allocated).unwrap(),
+        vec![false, false, false, false, true, true, true, true]
+    );
+
+    let too_large = Network::from_str("10.0.0.0/8").unwrap();
+    assert_eq!(
+        Network::coverage_bitmap(&too_large, &[]).unwrap_err(),
+        NetworkError::TooManyAddresses
+    );
+}
+
+#[test]
+fn test_parse_hex_address() {
+    assert_eq!(
+        Network::from_str("0xC0A80100/24").unwrap(),
+        Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)
+    );
+    assert_eq!(
+        Network::from_str("0x0a000001").unwrap(),
+        Network::new(Ipv4Addr::new(10, 0, 0, 1), 8)
+    );
+    for invalid in ["0xC0A801/24", "0xC0A8010000/24", "0xC0A8010G/24", "0x/24"] {
+        assert_eq!(
+            Network::from_str(invalid).unwrap_err(),
+            NetworkError::InvalidIpFormat
+        );
+    }
+}
+
+#[test]
+fn test_describe() {
+    let info = Network::from_str("192.168.1.64/26").unwrap().describe();
+    assert_eq!(
+        info,
+        NetworkInfo {
+            network: Ipv4Addr::new(192, 168, 1, 64),
+            prefix: 26,
+            netmask: Ipv4Addr::new(255, 255, 255, 192),
+            wildcard: Ipv4Addr::new(0, 0, 0, 63),
+            broadcast: Some(Ipv4Addr::new(192, 168, 1, 127)),
+            first_host: Some(Ipv4Addr::new(192, 168, 1, 65)),
+            last_host: Some(Ipv4Addr::new(192, 168, 1, 126)),
+            available_hosts: 62,
+            class: 'C',
+            scope: "private",
+        }
+    );
+    assert_eq!(Network::from_str("8.8.8.0/24").unwrap().scope(), "public");
+    assert_eq!(
+        Network::from_str("127.0.0.0/8").unwrap().scope(),
+        "loopback"
+    );
+}
+
+#[test]
+fn test_common_prefix_len() {
+    let a = Network::from_str("10.0.0.0/8").unwrap();
+    let b = Network::from_str("10.128.0.0/9").unwrap();
+    assert_eq!(a.common_prefix_len(&b), 8);
+    assert_eq!(b.common_prefix_len(&a), 8);
+    assert_eq!(a.common_prefix_len(&a), 32);
+
+    let c = Network::from_str("192.168.0.0/16").unwrap();
+    assert_eq!(a.common_prefix_len(&c), 0);
+}
+
+#[test]
+fn test_high_address_networks_do_not_wrap() {
+    let top = Network::from_str("255.255.255.192/26").unwrap();
+    assert_eq!(top.network_address(), Ipv4Addr::new(255, 255, 255, 192));
+    assert_eq!(top.broadcast_address(), Ipv4Addr::new(255, 255, 255, 255));
+    assert_eq!(
+        top.checked_broadcast(),
+        Some(Ipv4Addr::new(255, 255, 255, 255))
+    );
+    assert_eq!(
+        top.usable_range().unwrap(),
+        (
+            Ipv4Addr::new(255, 255, 255, 193),
+            Ipv4Addr::new(255, 255, 255, 254)
+        )
+    );
+    assert_eq!(top.hosts().count(), 62);
+    assert_eq!(top.hosts().last(), Some(Ipv4Addr::new(255, 255, 255, 254)));
+    assert_eq!(top.addresses().count(), 64);
+    assert_eq!(top.addresses().last(), Some(Ipv4Addr::BROADCAST));
+    assert_eq!(top.nth_host(61), Some(Ipv4Addr::new(255, 255, 255, 254)));
+    assert_eq!(top.nth_host(62), None);
+
+    let halves = Network::from_str("255.255.255.0/24")
+        .unwrap()
+        .split(2)
+        .unwrap();
+    assert_eq!(halves[1], top.ancestors().next().unwrap());
+    assert_eq!(halves[1].broadcast_address(), Ipv4Addr::BROADCAST);
+
+    let p2p = Network::from_str("255.255.255.254/31").unwrap();
+    assert_eq!(
+        p2p.usable_range().unwrap(),
+        (Ipv4Addr::new(255, 255, 255, 254), Ipv4Addr::BROADCAST)
+    );
+}
+
+#[test]
+fn test_complement_within() {
+    let parent = Network::from_str("192.168.1.0/24").unwrap();
+    let network = Network::from_str("192.168.1.0/26").unwrap();
+    assert_eq!(
+        network.complement_within(&parent).unwrap(),
+        vec![
+            Network::new(Ipv4Addr::new(192, 168, 1, 64), 26),
+            Network::new(Ipv4Addr::new(192, 168, 1, 128), 25),
+        ]
+    );
+    assert_eq!(
+        parent.complement_within(&network).unwrap_err(),
+        NetworkError::NotContained
+    );
+}
+
+#[test]
+fn test_first_and_last_address() {
+    let network = Network::from_str("10.0.0.5/30").unwrap();
+    assert_eq!(network.first_address(), Ipv4Addr::new(10, 0, 0, 4));
+    assert_eq!(network.last_address(), Ipv4Addr::new(10, 0, 0, 7));
+
+    let info = network.describe();
+    assert_eq!(info.first_host, Some(Ipv4Addr::new(10, 0, 0, 5)));
+    assert_eq!(info.last_host, Some(Ipv4Addr::new(10, 0, 0, 6)));
+}
+
+#[test]
+fn test_u32_pair_round_trip() {
+    let network = Network::from_str("192.168.1.0/24").unwrap();
+    let pair = network.to_u32_pair();
+    assert_eq!(pair, (0xC0A80100, 24));
+    assert_eq!(Network::from_u32_pair(pair.0, pair.1).unwrap(), network);
+
+    assert_eq!(
+        Network::from_u32_pair(0xC0A80101, 24).unwrap_err(),
+        NetworkError::MisalignedPrefix
+    );
+    assert_eq!(
+        Network::from_u32_pair(0xC0A80100, 33).unwrap_err(),
+        NetworkError::InvalidPrefixLength
+    );
+}
+
+#[test]
+fn test_spans_classful_boundary() {
+    assert!(Network::from_str("10.0.0.0/7")
+        .unwrap()
+        .spans_classful_boundary());
+    assert!(!Network::from_str("10.0.0.0/8")
+        .unwrap()
+        .spans_classful_boundary());
+    assert!(Network::from_str("172.16.0.0/12")
+        .unwrap()
+        .spans_classful_boundary());
+    assert!(!Network::from_str("192.168.1.0/26")
+        .unwrap()
+        .spans_classful_boundary());
+}
+
+#[test]
+fn test_align_down_and_up() {
+    let ip = Ipv4Addr::new(192, 168, 1, 37);
+    assert_eq!(Network::align_down(ip, 24), Ipv4Addr::new(192, 168, 1, 0));
+    assert_eq!(
+        Network::align_up(ip, 24),
+        Some(Ipv4Addr::new(192, 168, 2, 0))
+    );
+    assert_eq!(
+        Network::align_up(ip, 27),
+        Some(Ipv4Addr::new(192, 168, 1, 64))
+    );
+
+    // Already on a boundary, both directions stay put
+    let boundary = Ipv4Addr::new(192, 168, 1, 0);
+    assert_eq!(Network::align_down(boundary, 24), boundary);
+    assert_eq!(Network::align_up(boundary, 24), Some(boundary));
+
+    assert_eq!(Network::align_up(Ipv4Addr::new(255, 255, 255, 1), 24), None);
+    assert_eq!(Network::align_up(Ipv4Addr::new(10, 0, 0, 1), 0), None);
+}
+
+#[test]
+fn test_parse_tolerant_abbreviated() {
+    assert_eq!(
+        Network::parse_tolerant("10/8").unwrap(),
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 8)
+    );
+    assert_eq!(
+        Network::parse_tolerant("172.16/12").unwrap(),
+        Network::new(Ipv4Addr::new(172, 16, 0, 0), 12)
+    );
+    assert_eq!(
+        Network::parse_tolerant(" 192.168/16 ").unwrap(),
+        Network::new(Ipv4Addr::new(192, 168, 0, 0), 16)
+    );
+    assert_eq!(
+        Network::parse_tolerant("192.168.1.0/24").unwrap(),
+        Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)
+    );
+
+    // The strict parser and maskless shorthand still refuse it
+    assert!(Network::from_str("10/8").is_err());
+    assert!(Network::parse_tolerant("10").is_err());
+    assert!(Network::parse_tolerant("10.256/16").is_err());
+}
+
+#[test]
+fn test_network_bit_at() {
+    // 11000000.10101000.00000001.00000000
+    let network = Network::from_str("192.168.1.0/24").unwrap();
+    assert_eq!(network.network_bit_at(0), Some(true));
+    assert_eq!(network.network_bit_at(1), Some(true));
+    assert_eq!(network.network_bit_at(2), Some(false));
+    assert_eq!(network.network_bit_at(8), Some(true));
+    assert_eq!(network.network_bit_at(23), Some(true));
+    assert_eq!(network.network_bit_at(31), Some(false));
+    assert_eq!(network.network_bit_at(32), None);
+
+    assert!(!network.is_host_bit(0));
+    assert!(!network.is_host_bit(23));
+    assert!(network.is_host_bit(24));
+    assert!(network.is_host_bit(31));
+    assert!(!network.is_host_bit(32));
+}
+
+#[test]
+fn test_aggregate_lossless() {
+    let tiling = [
+        Network::from_str("10.0.0.0/25").unwrap(),
+        Network::from_str("10.0.0.128/26").unwrap(),
+        Network::from_str("10.0.0.192/26").unwrap(),
+        Network::from_str("10.0.0.200/29").unwrap(),
+    ];
+    assert_eq!(
+        Network::aggregate_lossless(&tiling).unwrap(),
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 24)
+    );
+
+    // 10.0.1.0/24 is missing, so the /22 would absorb it
+    let gap = [
+        Network::from_str("10.0.0.0/24").unwrap(),
+        Network::from_str("10.0.2.0/23").unwrap(),
+    ];
+    assert_eq!(
+        Network::aggregate_lossless(&gap).unwrap_err(),
+        NetworkError::Lossy
+    );
+    assert_eq!(
+        Network::aggregate_lossless(&[]).unwrap_err(),
+        NetworkError::EmptyNetworkList
+    );
+}
+
+#[test]
+fn test_parse_list_reports_failing_index() {
+    assert_eq!(
+        Network::parse_list(&["10.0.0.0/24", "10.0.1.0/24"]).unwrap(),
+        vec![
+            Network::new(Ipv4Addr::new(10, 0, 0, 0), 24),
+            Network::new(Ipv4Addr::new(10, 0, 1, 0), 24),
+        ]
+    );
+    assert_eq!(
+        Network::parse_list(&["10.0.0.0/24", "10.0.1.0/33", "bogus"]).unwrap_err(),
+        (1, NetworkError::InvalidPrefixLength)
+    );
+    let owned = vec![
+        String::from("10.0.0.0/8"),
+        String::from("10.0.0.0/255.0.255.0"),
+    ];
+    assert_eq!(
+        Network::parse_list(&owned).unwrap_err(),
+        (1, NetworkError::NonContiguousMask)
+    );
+}
+
+#[test]
+fn test_split_percent() {
+    let parent = Network::from_str("192.168.1.0/24").unwrap();
+    let first = |prefix| Network::new(Ipv4Addr::new(192, 168, 1, 0), prefix);
+    assert_eq!(parent.split_percent(25).unwrap(), first(26));
+    assert_eq!(parent.split_percent(30).unwrap(), first(25));
+    assert_eq!(parent.split_percent(50).unwrap(), first(25));
+    assert_eq!(parent.split_percent(100).unwrap(), first(24));
+    assert_eq!(parent.split_percent(1).unwrap(), first(30));
+
+    let whole = Network::from_str("0.0.0.0/0").unwrap();
+    assert_eq!(whole.split_percent(100).unwrap().prefix, 0);
+
+    for invalid in [0, 101] {
+        assert_eq!(
+            parent.split_percent(invalid).unwrap_err(),
+            NetworkError::InvalidHostsOrSubnets
+        );
+    }
+}
+
+#[test]
+fn test_network_error_paths_are_one_type() {
+    fn describe(error: subnetcalc::errors::NetworkError) -> String {
+        match error {
+            subnetcalc::subnet::NetworkError::InvalidPrefixLength => "prefix".to_string(),
+            other => other.to_string(),
+        }
+    }
+    let error: subnetcalc::subnet::NetworkError = Network::from_str("10.0.0.0/33").unwrap_err();
+    assert_eq!(describe(error), "prefix");
+    assert_eq!(
+        subnetcalc::subnet::NetworkError::EmptyNetworkList,
+        NetworkError::EmptyNetworkList
+    );
+}
+
+#[test]
+fn test_ipv6_prefix_math() {
+    let network = Ipv6Network::from_str("2001:db8:0:5::1/48").unwrap();
+    assert_eq!(
+        network.network_address(),
+        "2001:db8::".parse::<Ipv6Addr>().unwrap()
+    );
+    assert_eq!(
+        network.netmask_address(),
+        "ffff:ffff:ffff::".parse::<Ipv6Addr>().unwrap()
+    );
+    assert_eq!(
+        network.last_address(),
+        "2001:db8:0:ffff:ffff:ffff:ffff:ffff"
+            .parse::<Ipv6Addr>()
+            .unwrap()
+    );
+    assert_eq!(network.address_count(), 1 << 80);
+    assert_eq!(network.available_hosts(), 1 << 80);
+    assert!(network.contains_network(&Ipv6Network::from_str("2001:db8:0:5::/64").unwrap()));
+    assert!(!network.contains_network(&Ipv6Network::from_str("2001:db8::/32").unwrap()));
+
+    let info = network.describe();
+    assert_eq!(info.network, network.network_address());
+    assert_eq!(info.prefix, 48);
+    assert_eq!(info.last_address, network.last_address());
+
+    assert_eq!(Ipv6Network::from_str("::1").unwrap().address_count(), 1);
+    assert_eq!(
+        Ipv6Network::from_str("::/0").unwrap().address_count(),
+        u128::MAX
+    );
+}
+
+#[test]
+fn test_ipv6_aggregate_networks() {
+    let networks = [
+        Ipv6Network::from_str("2001:db8::/48").unwrap(),
+        Ipv6Network::from_str("2001:db8:1::/48").unwrap(),
+        Ipv6Network::from_str("2001:db8:3:1::/64").unwrap(),
+    ];
+    assert_eq!(
+        Ipv6Network::aggregate_networks(&networks).unwrap(),
+        Ipv6Network::from_str("2001:db8::/46").unwrap()
+    );
+
+    // Like IPv4, only the network addresses count, so a covering input can fall outside
+    let nested = [
+        Ipv6Network::from_str("2001:db8::/32").unwrap(),
+        Ipv6Network::from_str("2001:db8:1::/48").unwrap(),
+    ];
+    let aggregated = Ipv6Network::aggregate_networks(&nested).unwrap();
+    assert_eq!(aggregated, Ipv6Network::from_str("2001:db8::/47").unwrap());
+    assert!(!aggregated.contains_network(&nested[0]));
+
+    let copies = [
+        Ipv6Network::from_str("2001:db8::1/64").unwrap(),
+        Ipv6Network::from_str("2001:db8::2/64").unwrap(),
+    ];
+    assert_eq!(
+        Ipv6Network::aggregate_networks(&copies).unwrap(),
+        Ipv6Network::from_str("2001:db8::/64").unwrap()
+    );
+    assert_eq!(
+        Ipv6Network::aggregate_networks(&[]).unwrap_err(),
+        NetworkError::EmptyNetworkList
+    );
+}
+
+#[test]
+fn test_ip_network_aggregate_by_family() {
+    let mixed: Vec<IpNetwork> = [
+        "10.0.0.0/24",
+        "2001:db8::/48",
+        "10.0.1.0/24",
+        "2001:db8:1::/48",
+    ]
+    .iter()
+    .map(|s| IpNetwork::from_str(s).unwrap())
+    .collect();
+
+    let (v4, v6) = IpNetwork::split_families(&mixed);
+    assert_eq!(v4.len(), 2);
+    assert_eq!(v6.len(), 2);
+
+    assert_eq!(
+        IpNetwork::aggregate_by_family(&mixed).unwrap(),
+        vec![
+            IpNetwork::from_str("10.0.0.0/23").unwrap(),
+            IpNetwork::from_str("2001:db8::/47").unwrap(),
+        ]
+    );
+
+    // A single family gives a single result
+    assert_eq!(
+        IpNetwork::aggregate_by_family(&mixed[1..2]).unwrap(),
+        vec![IpNetwork::from_str("2001:db8::/48").unwrap()]
+    );
+    assert_eq!(
+        IpNetwork::aggregate_by_family(&[]).unwrap_err(),
+        NetworkError::EmptyNetworkList
+    );
+}
+
+#[test]
+fn test_aggregate_exact() {
+    let networks = [
+        Network::from_str("192.168.0.0/24").unwrap(),
+        Network::from_str("192.168.255.0/24").unwrap(),
+    ];
+    // The common prefix would absorb everything in between
+    assert_eq!(
+        Network::aggregate_networks(&networks).unwrap(),
+        Network::new(Ipv4Addr::new(192, 168, 0, 0), 16)
+    );
+    assert_eq!(
+        Network::aggregate_exact(&networks).unwrap(),
+        networks.to_vec()
+    );
+
+    let mergeable = [
+        Network::from_str("10.0.1.0/24").unwrap(),
+        Network::from_str("10.0.0.0/24").unwrap(),
+        Network::from_str("10.0.0.128/25").unwrap(),
+    ];
+    assert_eq!(
+        Network::aggregate_exact(&mergeable).unwrap(),
+        vec![Network::new(Ipv4Addr::new(10, 0, 0, 0), 23)]
+    );
+    assert_eq!(
+        Network::aggregate_exact(&[]).unwrap_err(),
+        NetworkError::EmptyNetworkList
+    );
+}
+
+#[test]
+fn test_ipv6_aggregate_exact() {
+    let networks: Vec<Ipv6Network> = [
+        "2001:db8:1::/48",
+        "2001:db8::/48",
+        "2001:db8::8000/113",
+        "2001:db8:ff::/48",
+    ]
+    .iter()
+    .map(|s| Ipv6Network::from_str(s).unwrap())
+    .collect();
+    assert_eq!(
+        Ipv6Network::aggregate_exact(&networks).unwrap(),
+        vec![
+            Ipv6Network::from_str("2001:db8::/47").unwrap(),
+            Ipv6Network::from_str("2001:db8:ff::/48").unwrap(),
+        ]
+    );
+
+    let mixed: Vec<IpNetwork> = ["2001:db8::/48", "10.0.1.0/24", "10.0.0.0/24"]
+        .iter()
+        .map(|s| IpNetwork::from_str(s).unwrap())
+        .collect();
+    assert_eq!(
+        IpNetwork::aggregate_exact_by_family(&mixed).unwrap(),
+        vec![
+            IpNetwork::from_str("10.0.0.0/23").unwrap(),
+            IpNetwork::from_str("2001:db8::/48").unwrap(),
+        ]
+    );
+    assert_eq!(
+        Ipv6Network::aggregate_exact(&[]).unwrap_err(),
+        NetworkError::EmptyNetworkList
+    );
+}
+
+#[test]
+fn test_absorbed_blocks() {
+    let inputs = [
+        Network::from_str("192.168.0.0/24").unwrap(),
+        Network::from_str("192.168.3.0/24").unwrap(),
+    ];
+    let aggregate = Network::aggregate_networks(&inputs).unwrap();
+    assert_eq!(
+        Network::absorbed_blocks(&aggregate, &inputs).unwrap(),
+        vec![
+            Network::new(Ipv4Addr::new(192, 168, 1, 0), 24),
+            Network::new(Ipv4Addr::new(192, 168, 2, 0), 24),
+        ]
+    );
+
+    let tiling = [
+        Network::from_str("10.0.0.0/24").unwrap(),
+        Network::from_str("10.0.1.0/24").unwrap(),
+    ];
+    let aggregate = Network::aggregate_networks(&tiling).unwrap();
+    assert!(Network::absorbed_blocks(&aggregate, &tiling)
+        .unwrap()
+        .is_empty());
+
+    // Inputs outside the aggregate are skipped, one covering it leaves nothing absorbed
+    let aggregate = Network::from_str("10.0.0.0/23").unwrap();
+    let outside = [
+        Network::from_str("10.0.0.0/24").unwrap(),
+        Network::from_str("10.9.0.0/24").unwrap(),
+    ];
+    assert_eq!(
+        Network::absorbed_blocks(&aggregate, &outside).unwrap(),
+        vec![Network::from_str("10.0.1.0/24").unwrap()]
+    );
+    let covering = [Network::from_str("10.0.0.0/16").unwrap()];
+    assert!(Network::absorbed_blocks(&aggregate, &covering)
+        .unwrap()
+        .is_empty());
+
+    // Both families reject an out of range aggregate instead of shifting by it
+    assert_eq!(
+        Network::absorbed_blocks(&Network::new(Ipv4Addr::new(10, 0, 0, 0), 33), &covering)
+            .unwrap_err(),
+        NetworkError::InvalidPrefixLength
+    );
+    assert_eq!(
+        Ipv6Network::absorbed_blocks(&Ipv6Network::new(Ipv6Addr::UNSPECIFIED, 129), &[])
+            .unwrap_err(),
+        NetworkError::InvalidIpv6PrefixLength
+    );
+}
+
+#[test]
+fn test_ipv6_absorbed_blocks() {
+    let inputs = [
+        Ipv6Network::from_str("2001:db8::/48").unwrap(),
+        Ipv6Network::from_str("2001:db8:3::/48").unwrap(),
+    ];
+    let aggregate = Ipv6Network::aggregate_networks(&inputs).unwrap();
+    assert_eq!(
+        Ipv6Network::absorbed_blocks(&aggregate, &inputs).unwrap(),
+        vec![
+            Ipv6Network::from_str("2001:db8:1::/48").unwrap(),
+            Ipv6Network::from_str("2001:db8:2::/48").unwrap(),
+        ]
+    );
+
+    let mixed = [
+        IpNetwork::from_str("10.0.0.0/24").unwrap(),
+        IpNetwork::from_str("2001:db8::/47").unwrap(),
+    ];
+    assert!(IpNetwork::absorbed_blocks(&mixed[1], &mixed)
+        .unwrap()
+        .is_empty());
+    assert_eq!(mixed[1].address_count(), 1 << 81);
+}
@@ -0,0 +1,33 @@
+use std::net::Ipv4Addr;
+use std::str::FromStr;
+use subnetcalc::subnet::Network;
+use subnetcalc::tagged::TaggedNetwork;
+
+#[test]
+fn test_aggregate_keeps_member_tags() {
+    let networks = [
+        TaggedNetwork::new(Network::from_str("10.0.0.0/24").unwrap(), "office"),
+        TaggedNetwork::new(Network::from_str("10.0.1.0/24").unwrap(), "lab"),
+    ];
+    let aggregate = TaggedNetwork::aggregate(&networks).unwrap();
+    assert_eq!(
+        aggregate.network,
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 23)
+    );
+    assert_eq!(aggregate.tags, vec!["office", "lab"]);
+}
+
+#[test]
+fn test_summarize_groups_tags() {
+    let networks = [
+        TaggedNetwork::new(Network::from_str("10.0.0.0/24").unwrap(), "office"),
+        TaggedNetwork::new(Network::from_str("10.0.1.0/24").unwrap(), "lab"),
+        TaggedNetwork::new(Network::from_str("10.0.8.0/24").unwrap(), "dmz"),
+    ];
+    let summary = TaggedNetwork::summarize(&networks);
+    assert_eq!(summary.len(), 2);
+    assert_eq!(summary[0].network.to_string(), "10.0.0.0/23");
+    assert_eq!(summary[0].tags, vec!["office", "lab"]);
+    assert_eq!(summary[1].network.to_string(), "10.0.8.0/24");
+    assert_eq!(summary[1].tags, vec!["dmz"]);
+}
@@ -0,0 +1,82 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use subnetcalc::errors::NetworkError;
+use subnetcalc::utils;
+
+#[test]
+fn test_ipv4_to_arpa() {
+    assert_eq!(
+        utils::ipv4_to_arpa(Ipv4Addr::new(1, 2, 3, 4)),
+        "4.3.2.1.in-addr.arpa"
+    );
+    assert_eq!(
+        utils::ipv4_to_arpa(Ipv4Addr::new(192, 168, 1, 10)),
+        "10.1.168.192.in-addr.arpa"
+    );
+}
+
+#[test]
+fn test_eui64_from_mac() {
+    let prefix = Ipv6Addr::from_str("2001:db8::").unwrap();
+    let result = utils::eui64_from_mac(prefix, [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]).unwrap();
+    assert_eq!(
+        result,
+        Ipv6Addr::from_str("2001:db8::21a:2bff:fe3c:4d5e").unwrap()
+    );
+
+    // A locally administered MAC has the bit cleared instead
+    let prefix = Ipv6Addr::from_str("fe80::").unwrap();
+    let result = utils::eui64_from_mac(prefix, [0x02, 0x00, 0x00, 0x00, 0x00, 0x01]).unwrap();
+    assert_eq!(result, Ipv6Addr::from_str("fe80::ff:fe00:1").unwrap());
+}
+
+#[test]
+fn test_eui64_from_mac_misaligned_prefix() {
+    let prefix = Ipv6Addr::from_str("2001:db8::1").unwrap();
+    let result = utils::eui64_from_mac(prefix, [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+    assert_eq!(result.unwrap_err(), NetworkError::MisalignedPrefix);
+}
+
+#[test]
+fn test_next_addr() {
+    assert_eq!(
+        utils::next_addr(Ipv4Addr::new(192, 168, 1, 255)),
+        Some(Ipv4Addr::new(192, 168, 2, 0))
+    );
+    assert_eq!(
+        utils::next_addr(Ipv4Addr::new(0, 0, 0, 0)),
+        Some(Ipv4Addr::new(0, 0, 0, 1))
+    );
+    assert_eq!(utils::next_addr(Ipv4Addr::BROADCAST), None);
+}
+
+#[test]
+fn test_prev_addr() {
+    assert_eq!(
+        utils::prev_addr(Ipv4Addr::new(192, 168, 2, 0)),
+        Some(Ipv4Addr::new(192, 168, 1, 255))
+    );
+    assert_eq!(
+        utils::prev_addr(Ipv4Addr::BROADCAST),
+        Some(Ipv4Addr::new(255, 255, 255, 254))
+    );
+    assert_eq!(utils::prev_addr(Ipv4Addr::UNSPECIFIED), None);
+}
+
+#[test]
+fn test_humanize_count() {
+    assert_eq!(utils::humanize_count(0), "0");
+    assert_eq!(utils::humanize_count(254), "254");
+    assert_eq!(utils::humanize_count(1_000), "1,000");
+    assert_eq!(utils::humanize_count(65_534), "65,534");
+    assert_eq!(utils::humanize_count(16_777_214), "16,777,214");
+    assert_eq!(utils::humanize_count(4_294_967_296), "4,294,967,296");
+}
+
+#[test]
+fn test_approximate_count() {
+    assert_eq!(utils::approximate_count(254), "254");
+    assert_eq!(utils::approximate_count(65_534), "~65.5K");
+    assert_eq!(utils::approximate_count(16_777_214), "~16.7M");
+    assert_eq!(utils::approximate_count(4_294_967_294), "~4.2B");
+}
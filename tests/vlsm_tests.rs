@@ -0,0 +1,95 @@
+use std::net::Ipv4Addr;
+use subnetcalc::errors::NetworkError;
+use subnetcalc::subnet::Network;
+use subnetcalc::vlsm::VlsmAllocator;
+
+#[test]
+fn test_allocate_three_blocks() {
+    let mut alloc = VlsmAllocator::new(Network::new(Ipv4Addr::new(192, 168, 1, 0), 24));
+
+    let a = alloc.allocate(100).unwrap();
+    let b = alloc.allocate(50).unwrap();
+    let c = alloc.allocate(25).unwrap();
+    assert_eq!(a, Network::new(Ipv4Addr::new(192, 168, 1, 0), 25));
+    assert_eq!(b, Network::new(Ipv4Addr::new(192, 168, 1, 128), 26));
+    assert_eq!(c, Network::new(Ipv4Addr::new(192, 168, 1, 192), 27));
+
+    assert!(!a.overlaps(&b));
+    assert!(!a.overlaps(&c));
+    assert!(!b.overlaps(&c));
+}
+
+#[test]
+fn test_allocate_best_fit() {
+    let mut alloc = VlsmAllocator::new(Network::new(Ipv4Addr::new(10, 0, 0, 0), 24));
+
+    let small = alloc.allocate(10).unwrap();
+    let large = alloc.allocate(100).unwrap();
+    let fill = alloc.allocate(10).unwrap();
+    assert_eq!(small, Network::new(Ipv4Addr::new(10, 0, 0, 0), 28));
+    assert_eq!(large, Network::new(Ipv4Addr::new(10, 0, 0, 128), 25));
+    // The leftover /28 next to the first allocation is the best fit
+    assert_eq!(fill, Network::new(Ipv4Addr::new(10, 0, 0, 16), 28));
+}
+
+#[test]
+fn test_allocate_exhausted() {
+    let mut alloc = VlsmAllocator::new(Network::new(Ipv4Addr::new(10, 0, 0, 0), 25));
+
+    assert!(alloc.allocate(100).is_ok());
+    assert_eq!(
+        alloc.allocate(1).unwrap_err(),
+        NetworkError::InsufficientBits
+    );
+    assert!(alloc.free_blocks().is_empty());
+}
+
+#[test]
+fn test_allocate_invalid_hosts() {
+    let mut alloc = VlsmAllocator::new(Network::new(Ipv4Addr::new(10, 0, 0, 0), 24));
+
+    assert_eq!(
+        alloc.allocate(0).unwrap_err(),
+        NetworkError::InvalidHostsOrSubnets
+    );
+    assert_eq!(
+        alloc.allocate(300).unwrap_err(),
+        NetworkError::InsufficientBits
+    );
+    assert_eq!(
+        alloc.allocate(u32::MAX).unwrap_err(),
+        NetworkError::InsufficientBits
+    );
+}
+
+#[test]
+fn test_plan_vlsm_multi() {
+    let parents = [
+        Network::new(Ipv4Addr::new(10, 0, 0, 0), 25),
+        Network::new(Ipv4Addr::new(10, 0, 5, 0), 25),
+    ];
+
+    let plan = Network::plan_vlsm_multi(&parents, &[100, 30, 30, 30]).unwrap();
+    assert_eq!(
+        plan,
+        vec![
+            (0, Network::new(Ipv4Addr::new(10, 0, 0, 0), 25)),
+            (1, Network::new(Ipv4Addr::new(10, 0, 5, 0), 27)),
+            (1, Network::new(Ipv4Addr::new(10, 0, 5, 32), 27)),
+            (1, Network::new(Ipv4Addr::new(10, 0, 5, 64), 27)),
+        ]
+    );
+
+    // Four /27s fit in a single /25, the best fit keeps filling the first parent
+    let plan = Network::plan_vlsm_multi(&parents, &[30, 30, 30, 30]).unwrap();
+    assert!(plan.iter().all(|(index, _)| *index == 0));
+
+    assert_eq!(
+        Network::plan_vlsm_multi(&parents, &[100, 100, 30]).unwrap_err(),
+        NetworkError::InsufficientBits
+    );
+    assert_eq!(
+        Network::plan_vlsm_multi(&[], &[30]).unwrap_err(),
+        NetworkError::EmptyNetworkList
+    );
+}